@@ -0,0 +1,196 @@
+//! Benchmarks for key derivation, report creation and verification, merged
+//! expansion, and matching.
+//!
+//! Run with `cargo bench`.  To track performance across releases, save a
+//! baseline from the previous release with `cargo bench -- --save-baseline
+//! <name>` and compare against it with `cargo bench -- --baseline <name>`.
+
+use std::collections::BTreeSet;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaChaRng;
+use tcn::*;
+
+/// The number of TCNs in a day's report, rotating every 15 minutes.
+const TCNS_PER_DAY: u16 = 24 * 60 / 15;
+
+fn rng() -> ChaChaRng {
+    // This is insecure!! It's only done for reproducibility.
+    ChaChaRng::from_seed([0x39; 32])
+}
+
+fn tck_at<S: CipherSuite>(rak: &ReportAuthorizationKey, index: u16) -> TemporaryContactKey<S> {
+    let mut tck = rak.initial_temporary_contact_key_for::<S>();
+    while tck.index() < index {
+        tck = tck.ratchet().expect("index <= u16::MAX");
+    }
+    tck
+}
+
+fn key_derivation(c: &mut Criterion) {
+    let rak = ReportAuthorizationKey::new(rng());
+    let tck = tck_at::<Sha256Ed25519>(&rak, 1000);
+    let blake3_tck = tck_at::<Blake3Ed25519>(&rak, 1000);
+
+    c.bench_function("temporary_contact_number", |b| {
+        b.iter(|| tck.temporary_contact_number())
+    });
+    c.bench_function("ratchet", |b| {
+        b.iter_batched(|| tck.clone(), |tck| tck.ratchet(), BatchSize::SmallInput)
+    });
+    c.bench_function("temporary_contact_number/blake3", |b| {
+        b.iter(|| blake3_tck.temporary_contact_number())
+    });
+    c.bench_function("ratchet/blake3", |b| {
+        b.iter_batched(
+            || blake3_tck.clone(),
+            |tck| tck.ratchet(),
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("report_authorization_key/new", |b| {
+        let mut rng = rng();
+        b.iter(|| ReportAuthorizationKey::new(&mut rng))
+    });
+}
+
+fn reports(c: &mut Criterion) {
+    let rak = ReportAuthorizationKey::new(rng());
+
+    // Creating a report ratchets up to tck_{j_1 - 1}, so its cost grows with j_1.
+    let mut group = c.benchmark_group("create_report");
+    for &j_1 in &[1, 1000, 10_000, u16::MAX] {
+        group.bench_with_input(BenchmarkId::from_parameter(j_1), &j_1, |b, &j_1| {
+            b.iter(|| rak.create_report(MemoType::CoEpiV1, b"symptom data".to_vec(), j_1, j_1))
+        });
+    }
+    group.finish();
+
+    let signed_report = rak
+        .create_report(MemoType::CoEpiV1, b"symptom data".to_vec(), 1, TCNS_PER_DAY)
+        .unwrap();
+    let mut bytes = Vec::new();
+    signed_report.write(&mut bytes).unwrap();
+    c.bench_function("verify", |b| {
+        b.iter_batched(
+            || signed_report.clone(),
+            SignedReport::verify,
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("verify_ref", |b| {
+        b.iter(|| SignedReportRef::parse(&bytes).unwrap().0.verify().unwrap())
+    });
+
+    let report = signed_report.verify().unwrap();
+    let mut group = c.benchmark_group("expand");
+    group.throughput(Throughput::Elements(TCNS_PER_DAY as u64));
+    group.bench_function(BenchmarkId::from_parameter(TCNS_PER_DAY), |b| {
+        b.iter(|| report.temporary_contact_numbers().count())
+    });
+    let blake3_report = rak
+        .create_suite_report::<Blake3Ed25519>(MemoType::CoEpiV1, Vec::new(), 1, TCNS_PER_DAY)
+        .unwrap()
+        .verify()
+        .unwrap();
+    group.bench_function(BenchmarkId::new("blake3", TCNS_PER_DAY), |b| {
+        b.iter(|| blake3_report.temporary_contact_numbers().count())
+    });
+    group.finish();
+}
+
+/// Merge a day of reports into a shuffled list of TCNs, as a server that
+/// publishes expanded TCNs does.
+fn merging(c: &mut Criterion) {
+    let mut rng = rng();
+    let num_reports = 1_000;
+    let reports = (0..num_reports)
+        .map(|_| {
+            ReportAuthorizationKey::new(&mut rng)
+                .create_report(MemoType::CoEpiV1, Vec::new(), 1, TCNS_PER_DAY)
+                .unwrap()
+                .verify()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("merge");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(num_reports as u64));
+    group.bench_function(BenchmarkId::new("shuffled", num_reports), |b| {
+        b.iter(|| {
+            let mut expander = ReportExpander::new();
+            for report in &reports {
+                expander.push_report(report);
+            }
+            expander.finish_shuffled(&mut rng).len()
+        })
+    });
+    group.finish();
+}
+
+/// Verify and expand a day of reports and intersect them with a device's
+/// observations, as a client does after each download.
+fn matching(c: &mut Criterion) {
+    let mut rng = rng();
+
+    let mut group = c.benchmark_group("match");
+    group.sample_size(10);
+    for &num_reports in &[1_000, 10_000] {
+        let raks = (0..num_reports)
+            .map(|_| ReportAuthorizationKey::new(&mut rng))
+            .collect::<Vec<_>>();
+        let reports = raks
+            .iter()
+            .map(|rak| {
+                let mut bytes = Vec::new();
+                rak.create_report(MemoType::CoEpiV1, Vec::new(), 1, TCNS_PER_DAY)
+                    .unwrap()
+                    .write(&mut bytes)
+                    .unwrap();
+                bytes
+            })
+            .collect::<Vec<_>>()
+            .concat();
+
+        // Two weeks of observations of 20 nearby devices a day, including a
+        // few TCNs from the reported keys.
+        let mut observed = BTreeSet::new();
+        for rak in raks.choose_multiple(&mut rng, 10) {
+            observed
+                .insert(tck_at::<Sha256Ed25519>(rak, TCNS_PER_DAY / 2).temporary_contact_number());
+        }
+        for _ in 0..14 * 20 {
+            let rak = ReportAuthorizationKey::new(&mut rng);
+            let mut tck = rak.initial_temporary_contact_key();
+            for _ in 0..TCNS_PER_DAY {
+                observed.insert(tck.temporary_contact_number());
+                tck = tck.ratchet().unwrap();
+            }
+        }
+
+        group.throughput(Throughput::Elements(num_reports as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_reports),
+            &reports,
+            |b, reports| {
+                b.iter(|| {
+                    let mut matches = 0;
+                    for signed_report in SignedReportRef::parse_all(reports) {
+                        let report = signed_report.unwrap().verify().unwrap();
+                        matches += report
+                            .temporary_contact_numbers()
+                            .filter(|tcn| observed.contains(tcn))
+                            .count();
+                    }
+                    assert_eq!(matches, 10);
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, key_derivation, reports, merging, matching);
+criterion_main!(benches);
@@ -0,0 +1,373 @@
+//! A population-scale contact simulation, for evaluating protocol parameters
+//! before changing app settings.
+//!
+//! Agents live in households and visit public locations in discrete steps, and
+//! agents at the same location are in contact.  Each agent broadcasts TCNs
+//! derived with the real `TemporaryContactKey` ratchet, and each contact
+//! observes the other's current TCN with some probability.  At the end, a
+//! fraction of agents report the TCNs from the last few days, every agent
+//! matches the published reports against its observations, and notifications
+//! are compared with the true contacts.
+//!
+//! Run with, for example,
+//! ```text
+//! cargo run --release --example simulation -- --agents 5000 --tcn-interval 15
+//! ```
+//! and `--help` for all parameters.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    env, process,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+use tcn::*;
+
+/// The length of a mobility step, in minutes.
+const STEP_MINUTES: u32 = 5;
+
+struct Params {
+    agents: usize,
+    days: u32,
+    household_size: usize,
+    locations: usize,
+    home_probability: f64,
+    tcn_interval: u32,
+    key_rotation: u32,
+    observation_probability: f64,
+    report_fraction: f64,
+    report_days: u32,
+    min_exposure: u32,
+    seed: u64,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            agents: 1000,
+            days: 14,
+            household_size: 3,
+            locations: 2000,
+            home_probability: 0.9,
+            tcn_interval: 15,
+            key_rotation: 24 * 60,
+            observation_probability: 0.5,
+            report_fraction: 0.02,
+            report_days: 7,
+            min_exposure: 15,
+            seed: 0,
+        }
+    }
+}
+
+const USAGE: &str = "\
+Usage: simulation [OPTIONS]
+
+Options:
+  --agents <N>                   number of agents [default: 1000]
+  --days <N>                     simulated days [default: 14]
+  --household-size <N>           agents sharing each home [default: 3]
+  --locations <N>                number of public locations [default: 2000]
+  --home-probability <P>         chance an agent is at home in a step [default: 0.9]
+  --tcn-interval <MINUTES>       TCN rotation interval [default: 15]
+  --key-rotation <MINUTES>       report authorization key rotation interval [default: 1440]
+  --observation-probability <P>  chance a contact observes a TCN in a step [default: 0.5]
+  --report-fraction <P>          fraction of agents who report [default: 0.02]
+  --report-days <N>              days of TCNs included in a report [default: 7]
+  --min-exposure <MINUTES>       contact time with a reporter that should notify [default: 15]
+  --seed <N>                     random seed [default: 0]";
+
+fn parse_value<T: FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{}` for `{}`", value, flag))
+}
+
+impl Params {
+    fn parse() -> Result<Params, String> {
+        let mut params = Params::default();
+        let mut args = env::args().skip(1);
+        while let Some(flag) = args.next() {
+            if flag == "--help" || flag == "-h" {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for `{}`", flag))?;
+            match flag.as_str() {
+                "--agents" => params.agents = parse_value(&flag, &value)?,
+                "--days" => params.days = parse_value(&flag, &value)?,
+                "--household-size" => params.household_size = parse_value(&flag, &value)?,
+                "--locations" => params.locations = parse_value(&flag, &value)?,
+                "--home-probability" => params.home_probability = parse_value(&flag, &value)?,
+                "--tcn-interval" => params.tcn_interval = parse_value(&flag, &value)?,
+                "--key-rotation" => params.key_rotation = parse_value(&flag, &value)?,
+                "--observation-probability" => {
+                    params.observation_probability = parse_value(&flag, &value)?
+                }
+                "--report-fraction" => params.report_fraction = parse_value(&flag, &value)?,
+                "--report-days" => params.report_days = parse_value(&flag, &value)?,
+                "--min-exposure" => params.min_exposure = parse_value(&flag, &value)?,
+                "--seed" => params.seed = parse_value(&flag, &value)?,
+                _ => return Err(format!("unknown option `{}`\n\n{}", flag, USAGE)),
+            }
+        }
+
+        if params.tcn_interval == 0 || params.key_rotation < params.tcn_interval {
+            return Err("the key rotation must be at least one TCN interval".into());
+        }
+        if params.key_rotation / params.tcn_interval >= u16::MAX as u32 {
+            return Err("a key rotation must span fewer than 65535 TCN intervals".into());
+        }
+        if params.household_size == 0 || params.locations == 0 {
+            return Err("households and locations must not be empty".into());
+        }
+        if params.report_days > params.days {
+            return Err("there must be no more report days than days".into());
+        }
+        Ok(params)
+    }
+
+    fn steps(&self) -> u32 {
+        self.days * 24 * 60 / STEP_MINUTES
+    }
+
+    fn households(&self) -> usize {
+        self.agents.div_ceil(self.household_size)
+    }
+}
+
+/// A report authorization key and the step at which it came into use.
+struct KeyPeriod {
+    rak: ReportAuthorizationKey,
+    start: u32,
+}
+
+struct Agent {
+    home: usize,
+    keys: Vec<KeyPeriod>,
+    tck: TemporaryContactKey,
+    observed: BTreeSet<TemporaryContactNumber>,
+}
+
+impl Agent {
+    /// The index of the TCN broadcast at `step` in the current key period.
+    fn tcn_index(params: &Params, period_start: u32, step: u32) -> u16 {
+        let minutes = (step - period_start) * STEP_MINUTES;
+        // Does not overflow, as checked when parsing parameters.
+        (1 + minutes / params.tcn_interval) as u16
+    }
+
+    /// Advance the agent's keys to `step`, returning its current TCN.
+    fn tcn_at<R: Rng>(
+        &mut self,
+        params: &Params,
+        step: u32,
+        rng: &mut R,
+    ) -> TemporaryContactNumber {
+        let start = self.keys.last().expect("agents have a key").start;
+        if (step - start) * STEP_MINUTES >= params.key_rotation {
+            // This is insecure!! Keys come from the seeded simulation RNG.
+            let rak = ReportAuthorizationKey::new(ChaChaRng::from_seed(rng.gen()));
+            self.tck = rak.initial_temporary_contact_key();
+            self.keys.push(KeyPeriod { rak, start: step });
+        }
+
+        let start = self.keys.last().expect("agents have a key").start;
+        let index = Agent::tcn_index(params, start, step);
+        while self.tck.index() < index {
+            self.tck = self.tck.clone().ratchet().expect("index < u16::MAX");
+        }
+        self.tck.temporary_contact_number()
+    }
+
+    /// Create reports disclosing the TCNs broadcast from `from_step` to
+    /// `to_step`, one per key period.
+    fn reports(&self, params: &Params, from_step: u32, to_step: u32) -> Vec<SignedReport> {
+        self.keys
+            .iter()
+            .enumerate()
+            .filter_map(|(i, period)| {
+                let end = self.keys.get(i + 1).map_or(to_step, |next| next.start);
+                if end <= from_step {
+                    return None;
+                }
+                let j_1 = Agent::tcn_index(params, period.start, from_step.max(period.start));
+                let j_2 = Agent::tcn_index(params, period.start, end - 1);
+                Some(
+                    period
+                        .rak
+                        .create_report(MemoType::CoEpiV1, Vec::new(), j_1, j_2)
+                        .expect("empty memo is not too long, so report creation cannot fail"),
+                )
+            })
+            .collect()
+    }
+}
+
+fn main() {
+    let params = Params::parse().unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    });
+    let mut rng = ChaChaRng::seed_from_u64(params.seed);
+    let steps = params.steps();
+    let report_start = steps - params.report_days * 24 * 60 / STEP_MINUTES;
+
+    let mut agents = (0..params.agents)
+        .map(|_| {
+            let rak = ReportAuthorizationKey::new(ChaChaRng::from_seed(rng.gen()));
+            Agent {
+                home: rng.gen_range(0..params.households()),
+                tck: rak.initial_temporary_contact_key(),
+                keys: vec![KeyPeriod { rak, start: 0 }],
+                observed: BTreeSet::new(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let num_reporters = (params.agents as f64 * params.report_fraction).round() as usize;
+    let mut reporters = vec![false; params.agents];
+    for &i in (0..params.agents)
+        .collect::<Vec<_>>()
+        .choose_multiple(&mut rng, num_reporters)
+    {
+        reporters[i] = true;
+    }
+
+    // Simulate broadcasts and observations, recording the true contact time
+    // of each agent with reporters during the reported days.
+    println!("Simulating {} agents for {} steps", params.agents, steps);
+    let broadcast_start = Instant::now();
+    let mut exposure_steps = vec![0u32; params.agents];
+    let mut contact_events = 0u64;
+    for step in 0..steps {
+        let mut locations: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (i, agent) in agents.iter().enumerate() {
+            let location = if rng.gen_bool(params.home_probability) {
+                agent.home
+            } else {
+                // Public locations are numbered after the homes.
+                params.households() + rng.gen_range(0..params.locations)
+            };
+            locations.entry(location).or_default().push(i);
+        }
+
+        let tcns = agents
+            .iter_mut()
+            .map(|agent| agent.tcn_at(&params, step, &mut rng))
+            .collect::<Vec<_>>();
+
+        for present in locations.values() {
+            for &a in present {
+                let mut exposed = false;
+                for &b in present.iter().filter(|&&b| b != a) {
+                    contact_events += 1;
+                    exposed |= reporters[b];
+                    if rng.gen_bool(params.observation_probability) {
+                        agents[a].observed.insert(tcns[b]);
+                    }
+                }
+                if exposed && step >= report_start {
+                    exposure_steps[a] += 1;
+                }
+            }
+        }
+    }
+    let broadcast_time = broadcast_start.elapsed();
+
+    // Reporters publish reports, which every agent downloads.
+    let mut published = Vec::new();
+    let mut num_reports = 0;
+    for (agent, _) in agents.iter().zip(&reporters).filter(|(_, &r)| r) {
+        for signed_report in agent.reports(&params, report_start, steps) {
+            signed_report
+                .write(&mut published)
+                .expect("writing to a Vec cannot fail");
+            num_reports += 1;
+        }
+    }
+
+    // Each agent verifies and expands the reports, which is the same work for
+    // every agent, so it is measured once.
+    let expansion_start = Instant::now();
+    let mut candidates = BTreeSet::new();
+    for signed_report in SignedReportRef::parse_all(&published) {
+        let report = signed_report
+            .expect("published reports are well-formed")
+            .verify()
+            .expect("published reports are valid");
+        candidates.extend(report.temporary_contact_numbers());
+    }
+    let expansion_time = expansion_start.elapsed();
+
+    let matching_start = Instant::now();
+    let notified = agents
+        .iter()
+        .map(|agent| agent.observed.intersection(&candidates).next().is_some())
+        .collect::<Vec<_>>();
+    let matching_time = matching_start.elapsed();
+
+    // Compare notifications of non-reporters with their true exposure.
+    let min_exposure_steps = params.min_exposure.div_ceil(STEP_MINUTES);
+    let (mut true_positives, mut false_positives, mut false_negatives) = (0, 0, 0);
+    for i in (0..params.agents).filter(|&i| !reporters[i]) {
+        let exposed = exposure_steps[i] >= min_exposure_steps.max(1);
+        match (notified[i], exposed) {
+            (true, true) => true_positives += 1,
+            (true, false) => false_positives += 1,
+            (false, true) => false_negatives += 1,
+            (false, false) => {}
+        }
+    }
+    let ratio = |n: usize, d: usize| if d == 0 { 1.0 } else { n as f64 / d as f64 };
+    let observations = agents.iter().map(|a| a.observed.len()).sum::<usize>();
+    let per_agent = |d: Duration| d / params.agents.max(1) as u32;
+
+    println!();
+    println!("contact events:        {}", contact_events);
+    println!(
+        "observed TCNs:         {} ({:.0} per agent)",
+        observations,
+        observations as f64 / params.agents as f64
+    );
+    println!("reporters:             {}", num_reporters);
+    println!(
+        "reports:               {} disclosing {} TCNs",
+        num_reports,
+        candidates.len()
+    );
+    println!(
+        "download per agent:    {} bytes ({:.1} bytes per disclosed TCN)",
+        published.len(),
+        ratio(published.len(), candidates.len())
+    );
+    println!(
+        "notified:              {} (exposed for at least {} minutes: {})",
+        true_positives + false_positives,
+        params.min_exposure,
+        true_positives + false_negatives
+    );
+    println!(
+        "precision:             {:.3}",
+        ratio(true_positives, true_positives + false_positives)
+    );
+    println!(
+        "recall:                {:.3}",
+        ratio(true_positives, true_positives + false_negatives)
+    );
+    println!(
+        "CPU per agent:         {:?} (verification and expansion) + {:?} (matching)",
+        expansion_time,
+        per_agent(matching_time)
+    );
+    println!(
+        "CPU for broadcasts:    {:?} ({:?} per agent)",
+        broadcast_time,
+        per_agent(broadcast_time)
+    );
+}
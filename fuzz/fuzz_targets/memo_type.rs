@@ -0,0 +1,13 @@
+#![no_main]
+use std::convert::TryFrom;
+
+use libfuzzer_sys::fuzz_target;
+use tcn::{Error, MemoType};
+
+fuzz_target!(|byte: u8| {
+    match MemoType::try_from(byte) {
+        Ok(memo_type) => assert_eq!(memo_type as u8, byte),
+        Err(Error::UnknownMemoType(t)) => assert_eq!(t, byte),
+        Err(e) => panic!("unexpected error {:?}", e),
+    }
+});
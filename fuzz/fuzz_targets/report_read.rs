@@ -0,0 +1,24 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use tcn::{Report, ReportRef};
+
+fuzz_target!(|data: &[u8]| {
+    let mut reader = data;
+    let read = Report::read(&mut reader);
+    let consumed = &data[..data.len() - reader.len()];
+
+    // The reader and the slice parser agree, and a successfully read report
+    // writes back exactly the bytes it was read from.
+    match (read, ReportRef::parse(data)) {
+        (Ok(report), Ok((view, _))) => {
+            let mut written = Vec::new();
+            report.write(&mut written).unwrap();
+            assert_eq!(written, consumed);
+            let mut parsed = Vec::new();
+            view.to_report().write(&mut parsed).unwrap();
+            assert_eq!(written, parsed);
+        }
+        (Err(_), Err(_)) => {}
+        (read, parsed) => panic!("read {:?} but parsed {:?}", read, parsed),
+    }
+});
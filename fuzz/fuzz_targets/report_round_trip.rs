@@ -0,0 +1,73 @@
+#![no_main]
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use tcn::{MemoType, ReportAuthorizationKey, ReportVersion, SignedReport};
+
+/// A valid report of any version, biased towards edge cases: `j_2 < j_1`,
+/// indices near `u16::MAX`, and memos up to the length limit of the version.
+#[derive(Arbitrary, Debug)]
+struct Input {
+    rak: [u8; 32],
+    version: u8,
+    covid_watch: bool,
+    memo_len: u16,
+    memo_byte: u8,
+    j_1: u16,
+    j_2: u16,
+    near_max: bool,
+}
+
+fuzz_target!(|input: Input| {
+    let rak = ReportAuthorizationKey::decode_from_slice(&input.rak).unwrap();
+    let memo_type = if input.covid_watch {
+        MemoType::CovidWatchV1
+    } else {
+        MemoType::CoEpiV1
+    };
+    let version = match input.version % 4 {
+        0 => ReportVersion::V0,
+        1 => ReportVersion::V1,
+        2 => ReportVersion::V2,
+        _ => ReportVersion::V3,
+    };
+    let memo_len = match version {
+        ReportVersion::V3 => input.memo_len,
+        _ => input.memo_len % 256,
+    };
+    let memo_data = vec![input.memo_byte; memo_len as usize];
+    let (j_1, j_2) = if input.near_max {
+        (u16::MAX - input.j_1 % 64, u16::MAX - input.j_2 % 64)
+    } else {
+        (input.j_1, input.j_2)
+    };
+
+    let signed_report = rak
+        .create_versioned_report(version, memo_type, memo_data.clone(), j_1, j_2)
+        .unwrap();
+
+    // Parse, write, parse again.
+    let mut bytes = Vec::new();
+    signed_report.write(&mut bytes).unwrap();
+    let parsed = SignedReport::read(&bytes[..]).unwrap();
+    let mut rewritten = Vec::new();
+    parsed.write(&mut rewritten).unwrap();
+    assert_eq!(bytes, rewritten);
+    let decoded = SignedReport::decode_from_slice(&bytes).unwrap();
+    assert_eq!(decoded.to_hex().unwrap(), parsed.to_hex().unwrap());
+
+    let report = parsed.verify().unwrap();
+    assert_eq!(report.version(), version);
+    assert_eq!(report.memo_type(), memo_type);
+    assert_eq!(report.memo_data(), &memo_data[..]);
+
+    // j_1 = 0 is clamped to 1, and an empty range discloses no TCNs.
+    let j_1 = j_1.max(1);
+    let expected = if j_2 >= j_1 {
+        (j_2 - j_1) as usize + 1
+    } else {
+        0
+    };
+    if expected <= 1024 {
+        assert_eq!(report.temporary_contact_numbers().count(), expected);
+    }
+});
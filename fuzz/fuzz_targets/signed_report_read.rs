@@ -0,0 +1,21 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use tcn::{SignedReport, SignedReportRef};
+
+fuzz_target!(|data: &[u8]| {
+    let mut reader = data;
+    let read = SignedReport::read(&mut reader);
+    let consumed = &data[..data.len() - reader.len()];
+
+    match (read, SignedReportRef::parse(data)) {
+        (Ok(signed_report), Ok((view, _))) => {
+            let mut written = Vec::new();
+            signed_report.write(&mut written).unwrap();
+            assert_eq!(written, consumed);
+            // Verification must not panic, and both views agree on it.
+            assert_eq!(view.verify().is_ok(), signed_report.verify().is_ok());
+        }
+        (Err(_), Err(_)) => {}
+        (read, parsed) => panic!("read {:?} but parsed {:?}", read, parsed),
+    }
+});
@@ -0,0 +1,26 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use tcn::TemporaryContactKey;
+
+fuzz_target!(|data: &[u8]| {
+    let read = TemporaryContactKey::read(data);
+    let decoded = data.get(..66).map(TemporaryContactKey::decode_from_slice);
+    match (read, decoded) {
+        (Ok(tck), Some(Ok(decoded))) => {
+            assert_eq!(tck, decoded);
+            let mut written = Vec::new();
+            tck.write(&mut written).unwrap();
+            assert_eq!(written, &data[..66]);
+
+            // Deriving and ratcheting any key must not panic.
+            let _ = tck.temporary_contact_number();
+            if let Some(next) = tck.ratchet() {
+                assert_eq!(next.index(), decoded.index() + 1);
+            } else {
+                assert_eq!(decoded.index(), u16::MAX);
+            }
+        }
+        (Err(_), None) => {}
+        (read, decoded) => panic!("read {:?} but decoded {:?}", read, decoded),
+    }
+});
@@ -0,0 +1,283 @@
+use core::{convert::TryFrom, fmt};
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeSet, vec::Vec};
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::{Error, ReportRef, SignedReportRef};
+#[cfg(feature = "alloc")]
+use super::{MemoType, Report, ReportAuthorizationKey, SignedReport};
+
+const H_TOKEN_DOMAIN_SEP: &[u8; 7] = b"H_TOKEN";
+
+/// The length of a token identifier.
+pub(crate) const TOKEN_ID_LEN: usize = 16;
+/// The length of a serialized [`VerificationToken`].
+pub(crate) const TOKEN_LEN: usize = TOKEN_ID_LEN + 64;
+
+/// Compute the digest of a token identifier, which is what the health
+/// authority signs.
+fn token_digest(id: &[u8; TOKEN_ID_LEN]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(H_TOKEN_DOMAIN_SEP)
+        .chain_update(id)
+        .finalize()
+        .into()
+}
+
+/// Signs the verification tokens issued by a health authority.
+///
+/// The secret key is zeroized when this value is dropped.
+#[derive(Clone)]
+pub struct HealthAuthorityKey {
+    pub(crate) sk: ed25519_zebra::SigningKey,
+}
+
+impl Drop for HealthAuthorityKey {
+    fn drop(&mut self) {
+        self.sk.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for HealthAuthorityKey {}
+
+/// Prints only the health authority verification key, never the secret.
+impl fmt::Debug for HealthAuthorityKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HealthAuthorityKey")
+            .field("vk", &ed25519_zebra::VerificationKeyBytes::from(&self.sk))
+            .field("sk", &"<redacted>")
+            .finish()
+    }
+}
+
+impl HealthAuthorityKey {
+    /// Initialize a new health authority key from a random number generator.
+    pub fn new<R: RngCore + CryptoRng>(rng: R) -> HealthAuthorityKey {
+        HealthAuthorityKey {
+            sk: ed25519_zebra::SigningKey::new(rng),
+        }
+    }
+
+    /// Get the health authority verification key, which servers use to check
+    /// tokens.
+    pub fn verification_key_bytes(&self) -> [u8; 32] {
+        ed25519_zebra::VerificationKeyBytes::from(&self.sk).into()
+    }
+
+    /// Issue a new single-use verification token, for instance to a patient
+    /// who tested positive.
+    pub fn issue_token<R: RngCore + CryptoRng>(&self, mut rng: R) -> VerificationToken {
+        let mut id = [0; TOKEN_ID_LEN];
+        rng.fill_bytes(&mut id);
+        VerificationToken {
+            id,
+            sig: self.sk.sign(&token_digest(&id)).into(),
+        }
+    }
+}
+
+/// A single-use token issued by a health authority, authorizing its holder
+/// to upload one report.
+///
+/// The token is bound to a report by placing it at the start of the report's
+/// memo data, where the report signature ties it to the report's
+/// verification key: a token redeemed with one report cannot be moved to a
+/// report signed by another key.  A server accepts the report only if the
+/// token was signed by the health authority and was not redeemed before.
+///
+/// Note that the health authority sees the token it issues, and the token is
+/// published with the report, so the health authority can link the report to
+/// the patient it issued the token to.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct VerificationToken {
+    pub(crate) id: [u8; TOKEN_ID_LEN],
+    pub(crate) sig: [u8; 64],
+}
+
+impl VerificationToken {
+    /// Get the identifier of the token, which is tracked to prevent reuse.
+    pub fn id(&self) -> &[u8; 16] {
+        &self.id
+    }
+
+    /// Check that the token was signed by the health authority with the
+    /// given verification key.
+    pub fn verify(&self, authority: &[u8; 32]) -> Result<(), Error> {
+        ed25519_zebra::VerificationKey::try_from(*authority)
+            .and_then(|vk| {
+                vk.verify(
+                    &ed25519_zebra::Signature::from_bytes(&self.sig),
+                    &token_digest(&self.id),
+                )
+            })
+            .map_err(|_| Error::InvalidVerificationToken)
+    }
+
+    /// Extract the token from the start of a report's memo data.
+    pub fn from_memo(memo_data: &[u8]) -> Result<VerificationToken, Error> {
+        if memo_data.len() < TOKEN_LEN {
+            return Err(Error::InvalidVerificationToken);
+        }
+        VerificationToken::decode_from_slice(&memo_data[..TOKEN_LEN])
+    }
+}
+
+/// Records which verification tokens have been redeemed, so that each token
+/// authorizes at most one report.
+///
+/// Servers implement this over their database; `BTreeSet<[u8; 16]>`
+/// implements it in memory.
+pub trait TokenLedger {
+    /// Mark the token with identifier `id` as redeemed, returning `false` if
+    /// it was already redeemed.
+    fn redeem(&mut self, id: &[u8; 16]) -> bool;
+}
+
+#[cfg(feature = "alloc")]
+impl TokenLedger for BTreeSet<[u8; 16]> {
+    fn redeem(&mut self, id: &[u8; 16]) -> bool {
+        self.insert(*id)
+    }
+}
+
+impl<'a> SignedReportRef<'a> {
+    /// Verify the source integrity of this report and the verification token
+    /// at the start of its memo, then redeem the token in `ledger`, producing
+    /// `Ok(ReportRef)` if all succeed.
+    ///
+    /// The token is only redeemed if the report and the token are valid.
+    pub fn redeem_token<L: TokenLedger>(
+        &self,
+        authority: &[u8; 32],
+        ledger: &mut L,
+    ) -> Result<ReportRef<'a>, Error> {
+        let report = self.verify()?;
+        redeem(report.memo_data(), authority, ledger)?;
+        Ok(report)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SignedReport {
+    /// Verify the source integrity of this report and the verification token
+    /// at the start of its memo, then redeem the token in `ledger`, producing
+    /// `Ok(Report)` if all succeed.
+    pub fn redeem_token<L: TokenLedger>(
+        self,
+        authority: &[u8; 32],
+        ledger: &mut L,
+    ) -> Result<Report, Error> {
+        let report = self.verify()?;
+        redeem(report.memo_data(), authority, ledger)?;
+        Ok(report)
+    }
+}
+
+fn redeem<L: TokenLedger>(
+    memo_data: &[u8],
+    authority: &[u8; 32],
+    ledger: &mut L,
+) -> Result<(), Error> {
+    let token = VerificationToken::from_memo(memo_data)?;
+    token.verify(authority)?;
+    if !ledger.redeem(&token.id) {
+        return Err(Error::TokenAlreadyRedeemed);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+impl ReportAuthorizationKey {
+    /// Create a report of potential exposure authorized by a health authority
+    /// verification token.
+    ///
+    /// The memo data is the token followed by `app_data`, which may be at
+    /// most 175 bytes long.  The other inputs and notes are the same as for
+    /// [`create_report`](ReportAuthorizationKey::create_report).
+    pub fn create_verified_report(
+        &self,
+        memo_type: MemoType,
+        token: &VerificationToken,
+        app_data: &[u8],
+        j_1: u16,
+        j_2: u16,
+    ) -> Result<SignedReport, Error> {
+        let mut memo_data = Vec::with_capacity(TOKEN_LEN + app_data.len());
+        memo_data.extend_from_slice(&token.id);
+        memo_data.extend_from_slice(&token.sig);
+        memo_data.extend_from_slice(app_data);
+        self.create_report(memo_type, memo_data, j_1, j_2)
+    }
+}
+
+/// An in-memory health authority, which issues verification tokens and
+/// redeems them itself.
+///
+/// This stands in for a health authority and its upload server in tests and
+/// demonstrations:
+///
+/// ```
+/// use tcn::*;
+///
+/// let mut authority = LocalHealthAuthority::new(rand::thread_rng());
+/// let token = authority.issue_token(rand::thread_rng());
+///
+/// let rak = ReportAuthorizationKey::new(rand::thread_rng());
+/// let signed_report = rak
+///     .create_verified_report(MemoType::CovidWatchV1, &token, b"", 20, 90)
+///     .unwrap();
+///
+/// // The token authorizes one report, once.
+/// assert!(authority.redeem_signed_report(signed_report.clone()).is_ok());
+/// assert!(authority.redeem_signed_report(signed_report).is_err());
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct LocalHealthAuthority {
+    key: HealthAuthorityKey,
+    redeemed: BTreeSet<[u8; 16]>,
+}
+
+#[cfg(feature = "alloc")]
+impl LocalHealthAuthority {
+    /// Initialize a new health authority from a random number generator.
+    pub fn new<R: RngCore + CryptoRng>(rng: R) -> LocalHealthAuthority {
+        LocalHealthAuthority {
+            key: HealthAuthorityKey::new(rng),
+            redeemed: BTreeSet::new(),
+        }
+    }
+
+    /// Get the health authority verification key.
+    pub fn verification_key_bytes(&self) -> [u8; 32] {
+        self.key.verification_key_bytes()
+    }
+
+    /// Issue a new single-use verification token.
+    pub fn issue_token<R: RngCore + CryptoRng>(&self, rng: R) -> VerificationToken {
+        self.key.issue_token(rng)
+    }
+
+    /// Check whether the token with identifier `id` has been redeemed.
+    pub fn is_redeemed(&self, id: &[u8; 16]) -> bool {
+        self.redeemed.contains(id)
+    }
+
+    /// Verify an uploaded report and redeem its token.
+    pub fn redeem<'a>(
+        &mut self,
+        signed_report: &SignedReportRef<'a>,
+    ) -> Result<ReportRef<'a>, Error> {
+        let authority = self.verification_key_bytes();
+        signed_report.redeem_token(&authority, &mut self.redeemed)
+    }
+
+    /// Verify an owned uploaded report and redeem its token.
+    pub fn redeem_signed_report(&mut self, signed_report: SignedReport) -> Result<Report, Error> {
+        let authority = self.verification_key_bytes();
+        signed_report.redeem_token(&authority, &mut self.redeemed)
+    }
+}
@@ -0,0 +1,359 @@
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use curve25519_dalek::{
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+};
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::{Error, ReportAuthorizationKey, ReportRef, SignedReportRef, TokenLedger};
+#[cfg(feature = "alloc")]
+use super::{MemoType, Report, SignedReport};
+
+const H_BLIND_DOMAIN_SEP: &[u8; 7] = b"H_BLIND";
+const H_BLIND_PROOF_DOMAIN_SEP: &[u8; 13] = b"H_BLIND_PROOF";
+
+/// The length of a serialized [`BlindAuthorization`].
+pub(crate) const BLIND_AUTHORIZATION_LEN: usize = 32;
+
+/// Hash a report verification key to the point the health authority
+/// evaluates its key on.
+fn hash_to_point(rvk: &[u8; 32]) -> RistrettoPoint {
+    RistrettoPoint::from_hash(
+        Sha512::new()
+            .chain_update(H_BLIND_DOMAIN_SEP)
+            .chain_update(rvk),
+    )
+}
+
+/// Compute the challenge of a proof that `log_B(public) = log_input(output)`.
+fn proof_challenge(
+    public: &CompressedRistretto,
+    input: &CompressedRistretto,
+    output: &CompressedRistretto,
+    a_1: &CompressedRistretto,
+    a_2: &CompressedRistretto,
+) -> Scalar {
+    Scalar::from_hash(
+        Sha512::new()
+            .chain_update(H_BLIND_PROOF_DOMAIN_SEP)
+            .chain_update(public.as_bytes())
+            .chain_update(input.as_bytes())
+            .chain_update(output.as_bytes())
+            .chain_update(a_1.as_bytes())
+            .chain_update(a_2.as_bytes()),
+    )
+}
+
+fn decompress(bytes: &[u8; 32]) -> Result<RistrettoPoint, Error> {
+    CompressedRistretto(*bytes)
+        .decompress()
+        .ok_or(Error::BlindAuthorizationFailed)
+}
+
+/// The key with which a health authority blindly authorizes reports.
+///
+/// This is a verifiable oblivious PRF key on ristretto255: the health
+/// authority evaluates it on a blinded hash of a report verification key,
+/// without learning the key, and later recognizes the unblinded result in
+/// the report.  Unlike a [`HealthAuthorityKey`](crate::HealthAuthorityKey),
+/// the same secret key both authorizes and checks reports, so the upload
+/// server must hold it.  The secret key is zeroized when this value is
+/// dropped.
+///
+/// ```
+/// use std::collections::BTreeSet;
+/// use tcn::*;
+///
+/// let authority = BlindAuthorityKey::new(rand::thread_rng());
+/// let rak = ReportAuthorizationKey::new(rand::thread_rng());
+///
+/// // The user sends a blinded request to the health authority...
+/// let (request, blinding) = rak.blind_authorization_request(rand::thread_rng());
+/// // ...which answers it after checking their test result...
+/// let response = authority.evaluate(&request, rand::thread_rng()).unwrap();
+/// // ...and the user unblinds the answer and reports with it.
+/// let authorization = blinding
+///     .finish(&response, &authority.public_key_bytes())
+///     .unwrap();
+/// let signed_report = rak
+///     .create_blind_authorized_report(MemoType::CovidWatchV1, &authorization, b"", 20, 90)
+///     .unwrap();
+///
+/// // The upload server checks the authorization and allows it only once.
+/// let mut ledger = BTreeSet::new();
+/// assert!(authority.redeem_signed_report(signed_report, &mut ledger).is_ok());
+/// ```
+#[derive(Clone)]
+pub struct BlindAuthorityKey {
+    pub(crate) sk: Scalar,
+}
+
+impl Drop for BlindAuthorityKey {
+    fn drop(&mut self) {
+        self.sk.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for BlindAuthorityKey {}
+
+/// Prints only the public key, never the secret.
+impl fmt::Debug for BlindAuthorityKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlindAuthorityKey")
+            .field("pk", &self.public_key_bytes())
+            .field("sk", &"<redacted>")
+            .finish()
+    }
+}
+
+impl BlindAuthorityKey {
+    /// Initialize a new blind authority key from a random number generator.
+    pub fn new<R: RngCore + CryptoRng>(mut rng: R) -> BlindAuthorityKey {
+        BlindAuthorityKey {
+            sk: Scalar::random(&mut rng),
+        }
+    }
+
+    /// Get the public key, with which users check that their requests were
+    /// answered with the same key as everyone else's.
+    pub fn public_key_bytes(&self) -> [u8; 32] {
+        RistrettoPoint::mul_base(&self.sk).compress().to_bytes()
+    }
+
+    /// Answer a blinded authorization request, with a proof that it was
+    /// answered with this key.
+    ///
+    /// The request reveals nothing about the report verification key it was
+    /// made for, so the health authority should only answer it after
+    /// checking the requester's eligibility out of band.
+    pub fn evaluate<R: RngCore + CryptoRng>(
+        &self,
+        request: &BlindRequest,
+        mut rng: R,
+    ) -> Result<BlindResponse, Error> {
+        let input = decompress(&request.point)?;
+        let output = self.sk * input;
+
+        // A Chaum-Pedersen proof that log_B(pk) = log_input(output).
+        let nonce = Scalar::random(&mut rng);
+        let a_1 = RistrettoPoint::mul_base(&nonce).compress();
+        let a_2 = (nonce * input).compress();
+        let output = output.compress();
+        let challenge = proof_challenge(
+            &RistrettoPoint::mul_base(&self.sk).compress(),
+            &CompressedRistretto(request.point),
+            &output,
+            &a_1,
+            &a_2,
+        );
+        Ok(BlindResponse {
+            point: output.to_bytes(),
+            challenge: challenge.to_bytes(),
+            response: (nonce - challenge * self.sk).to_bytes(),
+        })
+    }
+
+    /// Check the blind authorization at the start of a report's memo data
+    /// against the report verification key.
+    pub fn check(&self, report: &ReportRef<'_>) -> Result<(), Error> {
+        let authorization = BlindAuthorization::from_memo(report.memo_data())?;
+        let expected = self.sk * hash_to_point(report.verification_key_bytes());
+        // Compare points rather than encodings, as `RistrettoPoint` equality
+        // is constant-time.
+        if expected == decompress(&authorization.point)? {
+            Ok(())
+        } else {
+            Err(Error::BlindAuthorizationFailed)
+        }
+    }
+
+    /// Verify the source integrity of a report and its blind authorization,
+    /// then redeem the authorization in `ledger`, producing `Ok(ReportRef)`
+    /// if all succeed.
+    ///
+    /// Each report verification key is authorized once, so a user can
+    /// upload only one report with it.
+    pub fn redeem<'a, L: TokenLedger>(
+        &self,
+        signed_report: &SignedReportRef<'a>,
+        ledger: &mut L,
+    ) -> Result<ReportRef<'a>, Error> {
+        let report = signed_report.verify()?;
+        self.check(&report)?;
+        redeem(report.memo_data(), ledger)?;
+        Ok(report)
+    }
+
+    /// Verify the source integrity of an owned report and its blind
+    /// authorization, then redeem the authorization in `ledger`, producing
+    /// `Ok(Report)` if all succeed.
+    #[cfg(feature = "alloc")]
+    pub fn redeem_signed_report<L: TokenLedger>(
+        &self,
+        signed_report: SignedReport,
+        ledger: &mut L,
+    ) -> Result<Report, Error> {
+        let report = signed_report.verify()?;
+        self.check(&report.as_report_ref())?;
+        redeem(report.memo_data(), ledger)?;
+        Ok(report)
+    }
+}
+
+/// Mark an authorization as redeemed.  Authorizations are uniformly random
+/// points, so a prefix of their encoding identifies them.
+fn redeem<L: TokenLedger>(memo_data: &[u8], ledger: &mut L) -> Result<(), Error> {
+    let mut id = [0; 16];
+    id.copy_from_slice(&memo_data[..16]);
+    if !ledger.redeem(&id) {
+        return Err(Error::TokenAlreadyRedeemed);
+    }
+    Ok(())
+}
+
+/// A blinded request for a health authority to authorize reports with a
+/// report verification key.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BlindRequest {
+    pub(crate) point: [u8; 32],
+}
+
+/// A health authority's answer to a [`BlindRequest`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BlindResponse {
+    pub(crate) point: [u8; 32],
+    pub(crate) challenge: [u8; 32],
+    pub(crate) response: [u8; 32],
+}
+
+/// The secret state of a [`BlindRequest`], which the requester keeps to
+/// unblind the response.
+///
+/// The blinding factor is zeroized when this value is dropped.
+pub struct Blinding {
+    factor: Scalar,
+    request: RistrettoPoint,
+}
+
+impl Drop for Blinding {
+    fn drop(&mut self) {
+        self.factor.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Blinding {}
+
+/// Prints only the request, never the blinding factor.
+impl fmt::Debug for Blinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Blinding")
+            .field("request", &self.request.compress())
+            .field("factor", &"<redacted>")
+            .finish()
+    }
+}
+
+impl Blinding {
+    /// Check the health authority's proof, then unblind its response into an
+    /// authorization for reports with the requested report verification key.
+    ///
+    /// The proof ensures that the response was computed with the published
+    /// key `authority`, rather than a key singling out this requester.
+    pub fn finish(
+        self,
+        response: &BlindResponse,
+        authority: &[u8; 32],
+    ) -> Result<BlindAuthorization, Error> {
+        let public = decompress(authority)?;
+        let output = decompress(&response.point)?;
+        let challenge = Option::<Scalar>::from(Scalar::from_canonical_bytes(response.challenge))
+            .ok_or(Error::BlindAuthorizationFailed)?;
+        let s = Option::<Scalar>::from(Scalar::from_canonical_bytes(response.response))
+            .ok_or(Error::BlindAuthorizationFailed)?;
+
+        let a_1 = RistrettoPoint::mul_base(&s) + challenge * public;
+        let a_2 = s * self.request + challenge * output;
+        let expected = proof_challenge(
+            &CompressedRistretto(*authority),
+            &self.request.compress(),
+            &CompressedRistretto(response.point),
+            &a_1.compress(),
+            &a_2.compress(),
+        );
+        if expected != challenge {
+            return Err(Error::BlindAuthorizationFailed);
+        }
+
+        Ok(BlindAuthorization {
+            point: (self.factor.invert() * output).compress().to_bytes(),
+        })
+    }
+}
+
+/// A health authority's authorization of reports with one report
+/// verification key, obtained without revealing the key to it.
+///
+/// The authorization is bound to a report by placing it at the start of the
+/// report's memo data.  It is a deterministic function of the report
+/// verification key, so the health authority cannot link it to the request
+/// it answered.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BlindAuthorization {
+    pub(crate) point: [u8; 32],
+}
+
+impl BlindAuthorization {
+    /// Extract the authorization from the start of a report's memo data.
+    pub fn from_memo(memo_data: &[u8]) -> Result<BlindAuthorization, Error> {
+        if memo_data.len() < BLIND_AUTHORIZATION_LEN {
+            return Err(Error::BlindAuthorizationFailed);
+        }
+        BlindAuthorization::decode_from_slice(&memo_data[..BLIND_AUTHORIZATION_LEN])
+    }
+}
+
+impl ReportAuthorizationKey {
+    /// Request a health authority's blind authorization for reports with this
+    /// key, returning the request to send and the state needed to unblind
+    /// the response.
+    pub fn blind_authorization_request<R: RngCore + CryptoRng>(
+        &self,
+        mut rng: R,
+    ) -> (BlindRequest, Blinding) {
+        let rvk = <[u8; 32]>::from(ed25519_zebra::VerificationKeyBytes::from(&self.rak));
+        let factor = Scalar::random(&mut rng);
+        let request = factor * hash_to_point(&rvk);
+        (
+            BlindRequest {
+                point: request.compress().to_bytes(),
+            },
+            Blinding { factor, request },
+        )
+    }
+
+    /// Create a report of potential exposure carrying a blind authorization
+    /// for this key.
+    ///
+    /// The memo data is the authorization followed by `app_data`, which may be
+    /// at most 223 bytes long.  The other inputs and notes are the same as
+    /// for [`create_report`](ReportAuthorizationKey::create_report).
+    #[cfg(feature = "alloc")]
+    pub fn create_blind_authorized_report(
+        &self,
+        memo_type: MemoType,
+        authorization: &BlindAuthorization,
+        app_data: &[u8],
+        j_1: u16,
+        j_2: u16,
+    ) -> Result<SignedReport, Error> {
+        let mut memo_data = Vec::with_capacity(BLIND_AUTHORIZATION_LEN + app_data.len());
+        memo_data.extend_from_slice(&authorization.point);
+        memo_data.extend_from_slice(app_data);
+        self.create_report(memo_type, memo_data, j_1, j_2)
+    }
+}
@@ -0,0 +1,241 @@
+use core::{convert::TryFrom, fmt};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::{Error, ReportRef};
+#[cfg(feature = "alloc")]
+use super::{ReportPolicy, SignedReport, SignedReportRef};
+
+const H_BUNDLE_DOMAIN_SEP: &[u8; 8] = b"H_BUNDLE";
+
+/// The length of a bundle's sequence number.
+const BUNDLE_HEADER_LEN: usize = 8;
+const SIG_LEN: usize = 64;
+
+/// Compute the digest of a bundle's sequence number and reports, which is
+/// what the publisher signs.
+fn bundle_digest(body: &[u8]) -> [u8; 32] {
+    let mut digest = [0; 32];
+    digest.copy_from_slice(
+        &Sha256::new()
+            .chain_update(H_BUNDLE_DOMAIN_SEP)
+            .chain_update(body)
+            .finalize()[..],
+    );
+    digest
+}
+
+/// Signs bundles of reports published by a server.
+///
+/// Clients that trust the publisher check a single bundle signature, made
+/// with this key, instead of the signature of every report.  The secret key
+/// is zeroized when this value is dropped.
+#[derive(Clone)]
+pub struct PublisherKey {
+    pub(crate) sk: ed25519_zebra::SigningKey,
+}
+
+impl Drop for PublisherKey {
+    fn drop(&mut self) {
+        self.sk.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for PublisherKey {}
+
+/// Prints only the publisher verification key, never the secret.
+impl fmt::Debug for PublisherKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublisherKey")
+            .field("vk", &ed25519_zebra::VerificationKeyBytes::from(&self.sk))
+            .field("sk", &"<redacted>")
+            .finish()
+    }
+}
+
+impl PublisherKey {
+    /// Initialize a new publisher key from a random number generator.
+    pub fn new<R: RngCore + CryptoRng>(rng: R) -> PublisherKey {
+        PublisherKey {
+            sk: ed25519_zebra::SigningKey::new(rng),
+        }
+    }
+
+    /// Get the publisher verification key, which clients use to check
+    /// bundles.
+    pub fn verification_key_bytes(&self) -> [u8; 32] {
+        ed25519_zebra::VerificationKeyBytes::from(&self.sk).into()
+    }
+}
+
+/// Assembles a bundle of reports for publication, stripping their signatures.
+///
+/// Every report is verified, and checked against a [`ReportPolicy`] if one is
+/// set, before its signature is removed, so that a bundle only ever contains
+/// reports that were validly signed.
+///
+/// ```
+/// use tcn::*;
+///
+/// let publisher = PublisherKey::new(rand::thread_rng());
+/// let rak = ReportAuthorizationKey::new(rand::thread_rng());
+/// let signed_report = rak
+///     .create_report(MemoType::CoEpiV1, b"symptom data".to_vec(), 20, 90)
+///     .unwrap();
+///
+/// // The server verifies and strips uploaded reports...
+/// let mut builder = ReportBundleBuilder::new(1).with_policy(ReportPolicy::default());
+/// builder.push_signed_report(signed_report).unwrap();
+/// let bundle = builder.finish(&publisher);
+///
+/// // ...and clients check the publisher's signature instead.
+/// let bundle = ReportBundleRef::verify(&bundle, &publisher.verification_key_bytes()).unwrap();
+/// assert_eq!(bundle.sequence(), 1);
+/// assert_eq!(bundle.reports().count(), 1);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct ReportBundleBuilder {
+    sequence: u64,
+    policy: Option<ReportPolicy>,
+    // The sequence number followed by the stripped reports.
+    body: Vec<u8>,
+    count: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl ReportBundleBuilder {
+    /// Start a bundle with the given sequence number.
+    ///
+    /// The sequence number is signed with the bundle, so that clients can
+    /// detect a publisher serving an old bundle in place of a new one.
+    pub fn new(sequence: u64) -> ReportBundleBuilder {
+        ReportBundleBuilder {
+            sequence,
+            policy: None,
+            body: sequence.to_le_bytes().to_vec(),
+            count: 0,
+        }
+    }
+
+    /// Check every report against `policy` before adding it.
+    pub fn with_policy(mut self, policy: ReportPolicy) -> ReportBundleBuilder {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Get the sequence number of the bundle.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Get the number of reports in the bundle.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Check whether the bundle contains no reports.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Verify a signed report, then add it to the bundle without its
+    /// signature.
+    ///
+    /// Reports that fail verification or the policy are not added.
+    pub fn push(&mut self, signed_report: &SignedReportRef<'_>) -> Result<(), Error> {
+        let report = match &self.policy {
+            Some(policy) => signed_report.verify_with_policy(policy)?,
+            None => signed_report.verify()?,
+        };
+        self.append(&report)
+    }
+
+    /// Verify an owned signed report, then add it to the bundle without its
+    /// signature.
+    pub fn push_signed_report(&mut self, signed_report: SignedReport) -> Result<(), Error> {
+        let report = match &self.policy {
+            Some(policy) => signed_report.verify_with_policy(policy)?,
+            None => signed_report.verify()?,
+        };
+        self.append(&report.as_report_ref())
+    }
+
+    fn append(&mut self, report: &ReportRef<'_>) -> Result<(), Error> {
+        let start = self.body.len();
+        self.body.resize(start + report.encoded_len(), 0);
+        report.encode_to_slice(&mut self.body[start..])?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Sign the bundle, returning its serialization.
+    pub fn finish(mut self, publisher: &PublisherKey) -> Vec<u8> {
+        let sig = publisher.sk.sign(&bundle_digest(&self.body));
+        self.body.extend_from_slice(&<[u8; 64]>::from(sig));
+        self.body
+    }
+
+    /// Return the stripped reports as a bare stream, without a sequence number
+    /// or bundle signature, for clients that rely on the transport for
+    /// integrity.  The stream is parsed with [`ReportRef::parse_all`].
+    pub fn into_report_stream(mut self) -> Vec<u8> {
+        self.body.drain(..BUNDLE_HEADER_LEN);
+        self.body
+    }
+}
+
+/// A borrowed view of a bundle of reports whose publisher signature has been
+/// verified.
+///
+/// The reports in the bundle carry no signatures of their own: clients trust
+/// the publisher to have verified them before stripping their signatures.
+#[derive(Copy, Clone, Debug)]
+pub struct ReportBundleRef<'a> {
+    sequence: u64,
+    reports: &'a [u8],
+}
+
+impl<'a> ReportBundleRef<'a> {
+    /// Verify a serialized bundle against the publisher verification key,
+    /// producing `Ok(ReportBundleRef)` if successful.
+    ///
+    /// Only the bundle signature is checked here; the reports are parsed as
+    /// they are iterated over.
+    pub fn verify(bytes: &'a [u8], publisher: &[u8; 32]) -> Result<ReportBundleRef<'a>, Error> {
+        if bytes.len() < BUNDLE_HEADER_LEN + SIG_LEN {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        let (body, sig) = bytes.split_at(bytes.len() - SIG_LEN);
+        let sig = ed25519_zebra::Signature::from_bytes(
+            <&[u8; 64]>::try_from(sig).expect("slice has length 64"),
+        );
+        ed25519_zebra::VerificationKey::try_from(*publisher)
+            .and_then(|vk| vk.verify(&sig, &bundle_digest(body)))
+            .map_err(|_| Error::BundleVerificationFailed)?;
+
+        let (sequence, reports) = body.split_at(BUNDLE_HEADER_LEN);
+        Ok(ReportBundleRef {
+            sequence: u64::from_le_bytes(
+                <[u8; 8]>::try_from(sequence).expect("slice has length 8"),
+            ),
+            reports,
+        })
+    }
+
+    /// Get the sequence number of the bundle.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Iterate over the reports in the bundle.
+    ///
+    /// The iterator stops after yielding the first parse error.
+    pub fn reports(&self) -> impl Iterator<Item = Result<ReportRef<'a>, Error>> + 'a {
+        ReportRef::parse_all(self.reports)
+    }
+}
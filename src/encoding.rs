@@ -0,0 +1,185 @@
+use alloc::{string::String, vec::Vec};
+use core::{fmt, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use super::{Error, Report, ReportAuthorizationKey, SignedReport, TemporaryContactNumber};
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, Error> {
+    hex::decode(s).map_err(|_| Error::InvalidHex)
+}
+
+fn decode_base64(s: &str) -> Result<Vec<u8>, Error> {
+    STANDARD.decode(s).map_err(|_| Error::InvalidBase64)
+}
+
+impl TemporaryContactNumber {
+    /// Encode this temporary contact number as a lowercase hex string.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// Decode a temporary contact number from a hex string.
+    pub fn from_hex(s: &str) -> Result<TemporaryContactNumber, Error> {
+        TemporaryContactNumber::from_bytes(&decode_hex(s)?)
+    }
+
+    /// Encode this temporary contact number as a padded base64 string.
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.0)
+    }
+
+    /// Decode a temporary contact number from a padded base64 string.
+    pub fn from_base64(s: &str) -> Result<TemporaryContactNumber, Error> {
+        TemporaryContactNumber::from_bytes(&decode_base64(s)?)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<TemporaryContactNumber, Error> {
+        let mut tcn = [0; 16];
+        if bytes.len() != tcn.len() {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        tcn.copy_from_slice(bytes);
+        Ok(TemporaryContactNumber(tcn))
+    }
+}
+
+/// Formats the temporary contact number as lowercase hex.
+impl fmt::Display for TemporaryContactNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+/// Parses a temporary contact number from hex.
+impl FromStr for TemporaryContactNumber {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<TemporaryContactNumber, Error> {
+        TemporaryContactNumber::from_hex(s)
+    }
+}
+
+impl Report {
+    /// Encode this report as a lowercase hex string.
+    ///
+    /// This method fails only when the memo data is too long.
+    pub fn to_hex(&self) -> Result<String, Error> {
+        Ok(hex::encode(self.to_vec()?))
+    }
+
+    /// Decode a report from a hex string.
+    pub fn from_hex(s: &str) -> Result<Report, Error> {
+        Report::decode_from_slice(&decode_hex(s)?)
+    }
+
+    /// Encode this report as a padded base64 string.
+    ///
+    /// This method fails only when the memo data is too long.
+    pub fn to_base64(&self) -> Result<String, Error> {
+        Ok(STANDARD.encode(self.to_vec()?))
+    }
+
+    /// Decode a report from a padded base64 string.
+    pub fn from_base64(s: &str) -> Result<Report, Error> {
+        Report::decode_from_slice(&decode_base64(s)?)
+    }
+}
+
+/// Formats the report as padded base64.
+///
+/// Formatting fails if the memo data is too long to be serialized.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_base64().map_err(|_| fmt::Error)?)
+    }
+}
+
+/// Parses a report from padded base64.
+impl FromStr for Report {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Report, Error> {
+        Report::from_base64(s)
+    }
+}
+
+impl SignedReport {
+    /// Encode this signed report as a lowercase hex string.
+    ///
+    /// This method fails only when the memo data is too long.
+    pub fn to_hex(&self) -> Result<String, Error> {
+        Ok(hex::encode(self.to_vec()?))
+    }
+
+    /// Decode a signed report from a hex string.
+    ///
+    /// This does not verify the report's signature.
+    pub fn from_hex(s: &str) -> Result<SignedReport, Error> {
+        SignedReport::decode_from_slice(&decode_hex(s)?)
+    }
+
+    /// Encode this signed report as a padded base64 string.
+    ///
+    /// This method fails only when the memo data is too long.
+    pub fn to_base64(&self) -> Result<String, Error> {
+        Ok(STANDARD.encode(self.to_vec()?))
+    }
+
+    /// Decode a signed report from a padded base64 string.
+    ///
+    /// This does not verify the report's signature.
+    pub fn from_base64(s: &str) -> Result<SignedReport, Error> {
+        SignedReport::decode_from_slice(&decode_base64(s)?)
+    }
+}
+
+/// Formats the signed report as padded base64.
+///
+/// Formatting fails if the memo data is too long to be serialized.
+impl fmt::Display for SignedReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_base64().map_err(|_| fmt::Error)?)
+    }
+}
+
+/// Parses a signed report from padded base64, without verifying it.
+impl FromStr for SignedReport {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<SignedReport, Error> {
+        SignedReport::from_base64(s)
+    }
+}
+
+impl ReportAuthorizationKey {
+    /// Export the **secret** report authorization key as a lowercase hex string.
+    ///
+    /// Anyone holding the exported key can publish reports linked to every
+    /// temporary contact number derived from it, so the result should only be
+    /// written to secure storage.  There is deliberately no `Display`
+    /// implementation, and the `Debug` implementation redacts the secret.
+    pub fn expose_secret_hex(&self) -> String {
+        hex::encode(self.rak.as_ref())
+    }
+
+    /// Import a report authorization key previously exported with
+    /// [`expose_secret_hex`](ReportAuthorizationKey::expose_secret_hex).
+    pub fn from_secret_hex(s: &str) -> Result<ReportAuthorizationKey, Error> {
+        ReportAuthorizationKey::decode_from_slice(&decode_hex(s)?)
+    }
+
+    /// Export the **secret** report authorization key as a padded base64 string.
+    ///
+    /// The same caveats apply as for
+    /// [`expose_secret_hex`](ReportAuthorizationKey::expose_secret_hex).
+    pub fn expose_secret_base64(&self) -> String {
+        STANDARD.encode(self.rak.as_ref())
+    }
+
+    /// Import a report authorization key previously exported with
+    /// [`expose_secret_base64`](ReportAuthorizationKey::expose_secret_base64).
+    pub fn from_secret_base64(s: &str) -> Result<ReportAuthorizationKey, Error> {
+        ReportAuthorizationKey::decode_from_slice(&decode_base64(s)?)
+    }
+}
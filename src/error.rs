@@ -10,6 +10,7 @@ pub enum Error {
     #[error("Invalid TCN index in report")]
     InvalidReportIndex,
     /// An underlying I/O error occurred while parsing data.
+    #[cfg(feature = "std")]
     #[error("I/O error {0}")]
     Io(#[from] std::io::Error),
     /// An oversized memo field was supplied when creating a report.
@@ -18,4 +19,75 @@ pub enum Error {
     /// A report failed the source integrity check.
     #[error("Report verification failed")]
     ReportVerificationFailed,
+    /// A hex string contained a non-hex character or had odd length.
+    #[error("Invalid hex encoding")]
+    InvalidHex,
+    /// A base64 string contained a character outside the standard alphabet or
+    /// was not canonically padded.
+    #[error("Invalid base64 encoding")]
+    InvalidBase64,
+    /// Decoded data had the wrong length for the value being parsed.
+    #[error("Invalid encoded length: {0} bytes")]
+    InvalidLength(usize),
+    /// An output buffer was too small for the value being encoded.
+    #[error("Output buffer too small: {0} bytes needed")]
+    BufferTooSmall(usize),
+    /// A key storage container had an unsupported format version.
+    #[error("Unsupported key storage version {0}")]
+    UnsupportedStorageVersion(u8),
+    /// A key storage container was malformed.
+    #[error("Invalid key storage container")]
+    InvalidStorageContainer,
+    /// A key storage container could not be decrypted, either because the
+    /// password or wrapping key was wrong or because it was tampered with.
+    #[error("Key storage decryption failed")]
+    StorageDecryptionFailed,
+    /// A report's index range was empty, with `j_2 < j_1`.
+    #[error("Report index range is inverted: j_1 = {0}, j_2 = {1}")]
+    ReportRangeInverted(u16, u16),
+    /// A report disclosed more TCNs than allowed by a report policy.
+    #[error("Report discloses too many TCNs: {0}")]
+    ReportRangeTooLarge(u16),
+    /// A report's memo type was not allowed by a report policy.
+    #[error("Memo type {0} not allowed")]
+    MemoTypeNotAllowed(u8),
+    /// A report's memo was longer than allowed by a report policy.
+    #[error("Memo too long: {0} bytes")]
+    MemoTooLong(usize),
+    /// A report's memo did not satisfy the schema of its memo type.
+    #[error("Invalid memo for memo type {0}")]
+    InvalidMemo(u8),
+    /// A report bundle failed the publisher signature check.
+    #[error("Report bundle verification failed")]
+    BundleVerificationFailed,
+    /// A report used a wire format version that this implementation does not
+    /// support.
+    #[error("Unsupported report version {0}")]
+    UnsupportedReportVersion(u8),
+    /// A report identified a cipher suite that this implementation does not
+    /// support.
+    #[error("Unsupported cipher suite {0}")]
+    UnsupportedCipherSuite(u8),
+    /// A report's memo did not start with a verification token signed by the
+    /// health authority.
+    #[error("Invalid verification token")]
+    InvalidVerificationToken,
+    /// A report's verification token had already been redeemed.
+    #[error("Verification token already redeemed")]
+    TokenAlreadyRedeemed,
+    /// A blind authorization request, response or report authorization was
+    /// invalid.
+    #[error("Blind authorization failed")]
+    BlindAuthorizationFailed,
+    /// A private set intersection query or response was malformed.
+    #[error("Invalid private set intersection message")]
+    InvalidPsiMessage,
+    /// A TCN could not be split into, or reconstructed from, the given
+    /// number of shares with the given threshold.
+    #[error("Invalid share threshold {0} of {1} shares")]
+    InvalidShareThreshold(u8, usize),
+    /// A TCN share repeated an index, had index 0, or disagreed with the
+    /// other shares on the tag or threshold.
+    #[error("Invalid TCN share with index {0}")]
+    InvalidShare(u8),
 }
@@ -0,0 +1,146 @@
+use alloc::vec::Vec;
+use rand_core::{CryptoRng, RngCore};
+
+use super::{Report, ReportRef, TemporaryContactNumber};
+
+/// Sample uniformly from `0..bound`, rejecting the samples in the final
+/// partial range of `u64` so that every value is equally likely.
+fn uniform<R: RngCore>(rng: &mut R, bound: u64) -> u64 {
+    let limit = u64::MAX - u64::MAX % bound;
+    loop {
+        let sample = rng.next_u64();
+        if sample < limit {
+            return sample % bound;
+        }
+    }
+}
+
+/// Merges the temporary contact numbers of many reports into a single list,
+/// for servers that publish expanded TCNs rather than reports.
+///
+/// Each report expands to its TCNs in index order, so publishing the
+/// expansions one after another reveals which TCNs came from the same report
+/// and when each was broadcast.  The merged list is deduplicated and either
+/// sorted or shuffled, which decorrelates the TCNs from their reports and
+/// positions:
+///
+/// ```
+/// use tcn::*;
+///
+/// let mut expander = ReportExpander::new();
+/// for _ in 0..3 {
+///     let report = ReportAuthorizationKey::new(rand::thread_rng())
+///         .create_report(MemoType::CoEpiV1, Vec::new(), 1, 96)
+///         .unwrap()
+///         .verify()
+///         .unwrap();
+///     expander.push_report(&report);
+/// }
+/// let list = expander.finish_shuffled(rand::thread_rng());
+/// assert_eq!(list.len(), 3 * 96);
+///
+/// let mut bytes = vec![0; list.encoded_len()];
+/// list.encode_to_slice(&mut bytes).unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ReportExpander {
+    tcns: Vec<TemporaryContactNumber>,
+}
+
+impl ReportExpander {
+    /// Create an empty expander.
+    pub fn new() -> ReportExpander {
+        ReportExpander::default()
+    }
+
+    /// Add the temporary contact numbers disclosed by a verified report.
+    pub fn push_report(&mut self, report: &Report) {
+        self.tcns.extend(report.temporary_contact_numbers());
+    }
+
+    /// Add the temporary contact numbers disclosed by a verified borrowed
+    /// report.
+    pub fn push_report_ref(&mut self, report: &ReportRef<'_>) {
+        self.tcns.extend(report.temporary_contact_numbers());
+    }
+
+    /// Get the number of temporary contact numbers added so far, including
+    /// duplicates.
+    pub fn len(&self) -> usize {
+        self.tcns.len()
+    }
+
+    /// Check whether no temporary contact numbers have been added.
+    pub fn is_empty(&self) -> bool {
+        self.tcns.is_empty()
+    }
+
+    /// Deduplicate the temporary contact numbers and sort them.
+    ///
+    /// A sorted list compresses well and can be searched by clients.
+    pub fn finish_sorted(mut self) -> TcnList {
+        self.tcns.sort_unstable();
+        self.tcns.dedup();
+        TcnList {
+            tcns: self.tcns,
+            sorted: true,
+        }
+    }
+
+    /// Deduplicate the temporary contact numbers and shuffle them with `rng`.
+    pub fn finish_shuffled<R: RngCore + CryptoRng>(self, mut rng: R) -> TcnList {
+        let mut tcns = self.finish_sorted().tcns;
+        // Fisher-Yates.
+        for i in (1..tcns.len()).rev() {
+            let j = uniform(&mut rng, i as u64 + 1) as usize;
+            tcns.swap(i, j);
+        }
+        TcnList {
+            tcns,
+            sorted: false,
+        }
+    }
+}
+
+/// A deduplicated list of temporary contact numbers merged from many
+/// reports, produced by a [`ReportExpander`].
+///
+/// A list is serialized as the concatenation of its 16-byte TCNs, with no
+/// other framing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TcnList {
+    pub(crate) tcns: Vec<TemporaryContactNumber>,
+    pub(crate) sorted: bool,
+}
+
+impl TcnList {
+    /// Get the temporary contact numbers in the list.
+    pub fn as_slice(&self) -> &[TemporaryContactNumber] {
+        &self.tcns
+    }
+
+    /// Get the number of temporary contact numbers in the list.
+    pub fn len(&self) -> usize {
+        self.tcns.len()
+    }
+
+    /// Check whether the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.tcns.is_empty()
+    }
+
+    /// Check whether the list is in sorted order, so that lookups use binary
+    /// search.
+    pub fn is_sorted(&self) -> bool {
+        self.sorted
+    }
+
+    /// Check whether the list contains a temporary contact number.
+    pub fn contains(&self, tcn: &TemporaryContactNumber) -> bool {
+        if self.sorted {
+            self.tcns.binary_search(tcn).is_ok()
+        } else {
+            self.tcns.contains(tcn)
+        }
+    }
+}
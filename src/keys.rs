@@ -1,23 +1,46 @@
+use core::{convert::TryInto, fmt, marker::PhantomData};
+
 use rand_core::{CryptoRng, RngCore};
 use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
-pub use super::Error;
+use super::{CipherSuite, Sha256Ed25519};
 
-const H_TCK_DOMAIN_SEP: &[u8; 5] = b"H_TCK";
-const H_TCN_DOMAIN_SEP: &[u8; 5] = b"H_TCN";
+const H_RAK_DOMAIN_SEP: &[u8; 5] = b"H_RAK";
 
 /// Authorizes publication of a report of potential exposure.
-#[derive(Copy, Clone, Debug)]
+///
+/// The secret key is zeroized when this value is dropped.  It is deliberately
+/// not `Copy`, so every copy of the secret is an explicit `clone()`.
+#[derive(Clone)]
 pub struct ReportAuthorizationKey {
-    // We don't store rvk explicitly because it's cached inside the SecretKey.
-    pub(crate) rak: ed25519_zebra::SecretKey,
+    // We don't store rvk explicitly because it's cached inside the SigningKey.
+    pub(crate) rak: ed25519_zebra::SigningKey,
+}
+
+impl Drop for ReportAuthorizationKey {
+    fn drop(&mut self) {
+        self.rak.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for ReportAuthorizationKey {}
+
+/// Prints only the report verification key, never the secret.
+impl fmt::Debug for ReportAuthorizationKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReportAuthorizationKey")
+            .field("rvk", &ed25519_zebra::VerificationKeyBytes::from(&self.rak))
+            .field("rak", &"<redacted>")
+            .finish()
+    }
 }
 
 impl ReportAuthorizationKey {
     /// Initialize a new report authorization key from a random number generator.
     pub fn new<R: RngCore + CryptoRng>(rng: R) -> ReportAuthorizationKey {
         ReportAuthorizationKey {
-            rak: ed25519_zebra::SecretKey::new(rng),
+            rak: ed25519_zebra::SigningKey::new(rng),
         }
     }
 
@@ -26,47 +49,130 @@ impl ReportAuthorizationKey {
     /// Note: this function returns `tck_1`, the first temporary contact key that can be
     /// used to generate tcks.
     pub fn initial_temporary_contact_key(&self) -> TemporaryContactKey {
-        self.tck_0().ratchet().expect("0 < u16::MAX")
+        self.initial_temporary_contact_key_for::<Sha256Ed25519>()
     }
 
-    // This is pub(crate) because tck_0 shouldn't be used to generate a tcn.
-    pub(crate) fn tck_0(&self) -> TemporaryContactKey {
-        let rvk = ed25519_zebra::PublicKeyBytes::from(&self.rak);
-
-        let tck_bytes = {
-            // There's a bit of an awkward dance to get digests into fixed-size
-            // arrays because Rust doesn't have const generics (yet).
-            let mut bytes = [0; 32];
-            bytes.copy_from_slice(
-                &Sha256::default()
-                    .chain(H_TCK_DOMAIN_SEP)
-                    .chain(&self.rak)
-                    .result()[..],
-            );
-            bytes
-        };
+    /// Compute the initial temporary contact key derived from this report
+    /// authorization key with the cipher suite `S`.
+    ///
+    /// Reports about its TCNs must be created with
+    /// [`create_suite_report`](ReportAuthorizationKey::create_suite_report)
+    /// for the same suite.
+    pub fn initial_temporary_contact_key_for<S: CipherSuite>(&self) -> TemporaryContactKey<S> {
+        self.tck_0::<S>().ratchet().expect("0 < u16::MAX")
+    }
 
+    // This is pub(crate) because tck_0 shouldn't be used to generate a tcn.
+    pub(crate) fn tck_0<S: CipherSuite>(&self) -> TemporaryContactKey<S> {
+        let rak = self.rak.as_ref().try_into().expect("rak has length 32");
         TemporaryContactKey {
             index: 0,
-            rvk,
-            tck_bytes,
+            rvk: ed25519_zebra::VerificationKeyBytes::from(&self.rak),
+            tck_bytes: S::hash_tck_0(rak),
+            suite: PhantomData,
         }
     }
 }
 
+/// A master seed from which the report authorization key for each rotation
+/// period is deterministically derived.
+///
+/// Backing up the seed allows a restored device to report on any past period
+/// without having stored every report authorization key.  The seed is zeroized
+/// when dropped and redacted from `Debug` output.
+#[derive(Clone)]
+pub struct MasterSeed {
+    pub(crate) seed: [u8; 32],
+}
+
+impl Drop for MasterSeed {
+    fn drop(&mut self) {
+        self.seed.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for MasterSeed {}
+
+impl fmt::Debug for MasterSeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MasterSeed")
+            .field("seed", &"<redacted>")
+            .finish()
+    }
+}
+
+impl MasterSeed {
+    /// Initialize a new master seed from a random number generator.
+    pub fn new<R: RngCore + CryptoRng>(mut rng: R) -> MasterSeed {
+        let mut seed = [0; 32];
+        rng.fill_bytes(&mut seed);
+        MasterSeed { seed }
+    }
+
+    /// Derive the report authorization key for the rotation period with index
+    /// `period`, as `rak_p ← H_rak(seed || le_u32(p))`.
+    ///
+    /// Distinct periods yield independent, unlinkable keys.
+    pub fn report_authorization_key(&self, period: u32) -> ReportAuthorizationKey {
+        let mut rak_bytes = [0; 32];
+        rak_bytes.copy_from_slice(
+            &Sha256::new()
+                .chain_update(H_RAK_DOMAIN_SEP)
+                .chain_update(self.seed)
+                .chain_update(period.to_le_bytes())
+                .finalize()[..],
+        );
+        let rak = ReportAuthorizationKey {
+            rak: ed25519_zebra::SigningKey::from(rak_bytes),
+        };
+        rak_bytes.zeroize();
+        rak
+    }
+}
+
 /// A pseudorandom 128-bit value broadcast to nearby devices over Bluetooth.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct TemporaryContactNumber(pub [u8; 16]);
 
 /// A ratcheting key used to derive temporary contact numbers.
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub struct TemporaryContactKey {
+///
+/// The key material allows computing all subsequent temporary contact
+/// numbers, so it is zeroized when this value is dropped, including when it is
+/// consumed by [`ratchet`](TemporaryContactKey::ratchet).
+///
+/// Keys are derived with the cipher suite `S`, which defaults to
+/// [`Sha256Ed25519`].  The serialization does not record the suite, so only
+/// keys of the default suite can be decoded.
+#[derive(Clone, Eq, PartialEq)]
+pub struct TemporaryContactKey<S: CipherSuite = Sha256Ed25519> {
     pub(crate) index: u16,
-    pub(crate) rvk: ed25519_zebra::PublicKeyBytes,
+    pub(crate) rvk: ed25519_zebra::VerificationKeyBytes,
     pub(crate) tck_bytes: [u8; 32],
+    pub(crate) suite: PhantomData<S>,
 }
 
-impl TemporaryContactKey {
+impl<S: CipherSuite> Drop for TemporaryContactKey<S> {
+    fn drop(&mut self) {
+        self.tck_bytes.zeroize();
+    }
+}
+
+impl<S: CipherSuite> ZeroizeOnDrop for TemporaryContactKey<S> {}
+
+/// Prints the index, suite and report verification key, never the key
+/// material.
+impl<S: CipherSuite> fmt::Debug for TemporaryContactKey<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TemporaryContactKey")
+            .field("index", &self.index)
+            .field("suite", &S::ID)
+            .field("rvk", &self.rvk)
+            .field("tck_bytes", &"<redacted>")
+            .finish()
+    }
+}
+
+impl<S: CipherSuite> TemporaryContactKey<S> {
     /// The current ratchet index.
     pub fn index(&self) -> u16 {
         self.index
@@ -74,15 +180,7 @@ impl TemporaryContactKey {
 
     /// Compute the temporary contact number derived from this key.
     pub fn temporary_contact_number(&self) -> TemporaryContactNumber {
-        let mut bytes = [0; 16];
-        bytes.copy_from_slice(
-            &Sha256::default()
-                .chain(H_TCN_DOMAIN_SEP)
-                .chain(&self.index.to_le_bytes()[..])
-                .chain(&self.tck_bytes)
-                .result()[..16],
-        );
-        TemporaryContactNumber(bytes)
+        TemporaryContactNumber(S::hash_tcn(self.index, &self.tck_bytes))
     }
 
     /// Ratchet the key forward, producing a new key for a new temporary
@@ -91,30 +189,24 @@ impl TemporaryContactKey {
     /// # Returns
     /// - `Some(new_key)` if the current ratchet index is less than `u16::MAX`;
     /// - `None` if the current ratchet index is `u16::MAX`, signaling that the
-    /// report authorization key should be rotated.
-    pub fn ratchet(self) -> Option<TemporaryContactKey> {
+    ///   report authorization key should be rotated.
+    pub fn ratchet(self) -> Option<TemporaryContactKey<S>> {
         let TemporaryContactKey {
             index,
             rvk,
-            tck_bytes,
+            ref tck_bytes,
+            ..
         } = self;
 
         if let Some(next_index) = index.checked_add(1) {
-            let next_tck_bytes = {
-                let mut bytes = [0; 32];
-                bytes.copy_from_slice(
-                    &Sha256::default()
-                        .chain(H_TCK_DOMAIN_SEP)
-                        .chain(&rvk)
-                        .chain(&tck_bytes)
-                        .result()[..],
-                );
-                bytes
-            };
             Some(TemporaryContactKey {
                 rvk,
                 index: next_index,
-                tck_bytes: next_tck_bytes,
+                tck_bytes: S::hash_tck(
+                    rvk.as_ref().try_into().expect("rvk has length 32"),
+                    tck_bytes,
+                ),
+                suite: PhantomData,
             })
         } else {
             None
@@ -58,15 +58,72 @@
 //! // The slice is offset by 1 because tcn_0 is not included.
 //! assert_eq!(&recomputed_tcns[..], &tcns[20 - 1..90]);
 //! ```
+//!
+//! # Features
+//!
+//! The crate supports `no_std` environments such as wearables.  Key
+//! derivation, TCN generation, report creation and verification work without
+//! any features, using [`ReportRef`]s and slice-based encoders and decoders.
+//!
+//! - `alloc` enables the owned [`Report`] and [`SignedReport`] types and the
+//!   hex and base64 text encodings;
+//! - `std` (enabled by default) additionally enables serialization through
+//!   `io::Read` and `io::Write`;
+//! - `storage` (enabled by default) enables [`KeyRing`], an encrypted at-rest
+//!   storage container for keys and ratchet state;
+//! - `blind-auth` enables blind authorization of reports by a health
+//!   authority, through `BlindAuthorityKey`;
+//! - `psi` enables the private set intersection matching mode, through
+//!   `PsiServer` and `PsiClient`.
 
-#![doc(html_root_url = "https://docs.rs/tcn/0.4.1")]
+#![doc(html_root_url = "https://docs.rs/tcn/0.5.0")]
 #![deny(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
+mod authority;
+#[cfg(feature = "blind-auth")]
+mod blind;
+mod bundle;
+#[cfg(feature = "alloc")]
+mod encoding;
 mod error;
+#[cfg(feature = "alloc")]
+mod expand;
 mod keys;
+mod policy;
+#[cfg(feature = "psi")]
+mod psi;
 mod report;
 mod serialize;
+mod share;
+#[cfg(feature = "storage")]
+mod storage;
+mod suite;
 
+#[cfg(feature = "alloc")]
+pub use authority::LocalHealthAuthority;
+pub use authority::{HealthAuthorityKey, TokenLedger, VerificationToken};
+#[cfg(feature = "blind-auth")]
+pub use blind::{BlindAuthorityKey, BlindAuthorization, BlindRequest, BlindResponse, Blinding};
+#[cfg(feature = "alloc")]
+pub use bundle::ReportBundleBuilder;
+pub use bundle::{PublisherKey, ReportBundleRef};
 pub use error::Error;
-pub use keys::{ReportAuthorizationKey, TemporaryContactKey, TemporaryContactNumber};
-pub use report::{MemoType, Report, SignedReport};
+#[cfg(feature = "alloc")]
+pub use expand::{ReportExpander, TcnList};
+pub use keys::{MasterSeed, ReportAuthorizationKey, TemporaryContactKey, TemporaryContactNumber};
+pub use policy::{MemoPolicy, MemoSchema, ReportPolicy};
+#[cfg(feature = "psi")]
+pub use psi::{PsiClient, PsiQuery, PsiResponse, PsiServer};
+pub use report::{MemoType, ReportRef, ReportVersion, SignedReportRef};
+#[cfg(feature = "alloc")]
+pub use report::{Report, SignedReport};
+#[cfg(feature = "alloc")]
+pub use share::ShareReassembler;
+pub use share::TcnShare;
+#[cfg(feature = "storage")]
+pub use storage::{KeyRing, KeyRingEntry, PasswordParams};
+pub use suite::{Blake3Ed25519, CipherSuite, CipherSuiteId, Sha256Ed25519};
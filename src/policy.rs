@@ -0,0 +1,184 @@
+use super::{Error, MemoType, ReportRef, SignedReportRef};
+#[cfg(feature = "alloc")]
+use super::{Report, SignedReport};
+
+/// The number of memo types that a [`ReportPolicy`] can allow.
+const MEMO_TYPES: usize = 2;
+
+/// The position of a memo type in a policy's memo policies, or `None` for the
+/// reserved memo type, which no report can carry.
+fn memo_type_slot(memo_type: MemoType) -> Option<usize> {
+    match memo_type {
+        MemoType::CoEpiV1 => Some(0),
+        MemoType::CovidWatchV1 => Some(1),
+        MemoType::Reserved => None,
+    }
+}
+
+/// A check on the contents of a memo field.
+#[derive(Copy, Clone, Debug)]
+pub enum MemoSchema {
+    /// Any memo data is accepted.
+    Any,
+    /// The memo data must be valid UTF-8.
+    Utf8,
+    /// The memo data must be accepted by the given function.
+    Custom(fn(&[u8]) -> bool),
+}
+
+impl MemoSchema {
+    fn accepts(&self, memo_data: &[u8]) -> bool {
+        match self {
+            MemoSchema::Any => true,
+            MemoSchema::Utf8 => core::str::from_utf8(memo_data).is_ok(),
+            MemoSchema::Custom(f) => f(memo_data),
+        }
+    }
+}
+
+/// The memo fields accepted for one memo type.
+#[derive(Copy, Clone, Debug)]
+pub struct MemoPolicy {
+    /// The largest accepted memo, in bytes.
+    pub max_len: usize,
+    /// The schema the memo data must satisfy.
+    pub schema: MemoSchema,
+}
+
+impl Default for MemoPolicy {
+    /// Accept any memo of up to 255 bytes, the limit of reports before
+    /// version 3, so that extended memos must be allowed explicitly.
+    fn default() -> MemoPolicy {
+        MemoPolicy {
+            max_len: 255,
+            schema: MemoSchema::Any,
+        }
+    }
+}
+
+/// A validation policy for reports, enforced in addition to the signature
+/// check.
+///
+/// The signature only establishes that a report was created by the holder of
+/// its report authorization key, who may still choose an index range that
+/// discloses nothing (`j_2 < j_1`), a range that forces every client to
+/// expand up to 65535 TCNs, or a memo that no application understands.  A
+/// server can reject such reports before publishing them, and clients can
+/// skip them, by checking them against a `ReportPolicy`:
+///
+/// ```
+/// use tcn::*;
+///
+/// let policy = ReportPolicy::new(96 * 14)
+///     .allow_memo_type(MemoType::CoEpiV1, MemoPolicy::default());
+///
+/// let rak = ReportAuthorizationKey::new(rand::thread_rng());
+/// let signed_report = rak
+///     .create_report(MemoType::CoEpiV1, b"symptom data".to_vec(), 1, u16::MAX)
+///     .unwrap();
+/// assert!(signed_report.verify_with_policy(&policy).is_err());
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct ReportPolicy {
+    max_index_span: u16,
+    memo_policies: [Option<MemoPolicy>; MEMO_TYPES],
+}
+
+impl Default for ReportPolicy {
+    /// Accept reports of up to two weeks of TCNs rotated every 15 minutes,
+    /// with any memo of the CoEpi and CovidWatch memo types.
+    fn default() -> ReportPolicy {
+        ReportPolicy::new(14 * 24 * 4)
+            .allow_memo_type(MemoType::CoEpiV1, MemoPolicy::default())
+            .allow_memo_type(MemoType::CovidWatchV1, MemoPolicy::default())
+    }
+}
+
+impl ReportPolicy {
+    /// Create a policy accepting reports that disclose between 1 and
+    /// `max_index_span` TCNs.
+    ///
+    /// No memo types are allowed until they are added with
+    /// [`allow_memo_type`](ReportPolicy::allow_memo_type).
+    pub fn new(max_index_span: u16) -> ReportPolicy {
+        ReportPolicy {
+            max_index_span,
+            memo_policies: [None; MEMO_TYPES],
+        }
+    }
+
+    /// Allow reports with the given memo type, whose memos satisfy `memo`.
+    ///
+    /// [`MemoType::Reserved`] is never allowed, as no report can carry it;
+    /// allowing it leaves the policy unchanged.
+    pub fn allow_memo_type(mut self, memo_type: MemoType, memo: MemoPolicy) -> ReportPolicy {
+        if let Some(slot) = memo_type_slot(memo_type) {
+            self.memo_policies[slot] = Some(memo);
+        }
+        self
+    }
+
+    /// Get the largest number of TCNs an accepted report may disclose.
+    pub fn max_index_span(&self) -> u16 {
+        self.max_index_span
+    }
+
+    /// Get the memo policy for a memo type, or `None` if the type is not
+    /// allowed.
+    pub fn memo_policy(&self, memo_type: MemoType) -> Option<MemoPolicy> {
+        memo_type_slot(memo_type).and_then(|slot| self.memo_policies[slot])
+    }
+
+    /// Check a report against this policy.
+    ///
+    /// This does not verify the report's signature.
+    pub fn check(&self, report: &ReportRef<'_>) -> Result<(), Error> {
+        let (j_1, j_2) = (report.j_1, report.j_2);
+        if j_2 < j_1 {
+            return Err(Error::ReportRangeInverted(j_1, j_2));
+        }
+        // Does not overflow, as j_1 > 0.
+        let span = j_2 - j_1 + 1;
+        if span > self.max_index_span {
+            return Err(Error::ReportRangeTooLarge(span));
+        }
+
+        let memo = self
+            .memo_policy(report.memo_type)
+            .ok_or(Error::MemoTypeNotAllowed(report.memo_type as u8))?;
+        if report.memo_data.len() > memo.max_len {
+            return Err(Error::MemoTooLong(report.memo_data.len()));
+        }
+        if !memo.schema.accepts(report.memo_data) {
+            return Err(Error::InvalidMemo(report.memo_type as u8));
+        }
+        Ok(())
+    }
+
+    /// Check an owned report against this policy.
+    ///
+    /// This does not verify the report's signature.
+    #[cfg(feature = "alloc")]
+    pub fn check_report(&self, report: &Report) -> Result<(), Error> {
+        self.check(&report.as_report_ref())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SignedReport {
+    /// Check this report against `policy`, then verify its source integrity,
+    /// producing `Ok(Report)` if both succeed.
+    pub fn verify_with_policy(self, policy: &ReportPolicy) -> Result<Report, Error> {
+        policy.check_report(&self.report)?;
+        self.verify()
+    }
+}
+
+impl<'a> SignedReportRef<'a> {
+    /// Check this report against `policy`, then verify its source integrity,
+    /// producing `Ok(ReportRef)` if both succeed.
+    pub fn verify_with_policy(&self, policy: &ReportPolicy) -> Result<ReportRef<'a>, Error> {
+        policy.check(&self.report)?;
+        self.verify()
+    }
+}
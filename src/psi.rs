@@ -0,0 +1,259 @@
+use core::{convert::TryInto, fmt};
+
+use alloc::{collections::BTreeSet, vec::Vec};
+use curve25519_dalek::{
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+};
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::{Error, Report, ReportRef, TemporaryContactNumber};
+
+const H_PSI_DOMAIN_SEP: &[u8; 5] = b"H_PSI";
+
+/// The length of an encoded group element.
+pub(crate) const PSI_ELEMENT_LEN: usize = 32;
+
+/// Hash a temporary contact number to a point.
+fn hash_to_point(tcn: &TemporaryContactNumber) -> RistrettoPoint {
+    RistrettoPoint::from_hash(
+        Sha512::new()
+            .chain_update(H_PSI_DOMAIN_SEP)
+            .chain_update(tcn.0),
+    )
+}
+
+fn decompress(bytes: &[u8; 32]) -> Result<RistrettoPoint, Error> {
+    CompressedRistretto(*bytes)
+        .decompress()
+        .ok_or(Error::InvalidPsiMessage)
+}
+
+/// The server side of private set intersection matching.
+///
+/// In this matching mode, the server does not publish the TCNs of the reports
+/// it receives.  Instead, it publishes each reported TCN `y` as
+/// `a·H_psi(y)` under its secret scalar `a`, which reveals nothing about `y`.
+/// A client that wants to check its observations sends them blinded as
+/// `b·H_psi(x)`, under its own secret scalar `b`, and the server answers with
+/// `a·b·H_psi(x)`; the client removes `b` and compares `a·H_psi(x)` with the
+/// published set.  The client thus learns only which of its observations
+/// were reported, and the server learns nothing about them.
+///
+/// The client must trust the server to use the same scalar for the
+/// published set and the answers, as it cannot check this.
+///
+/// ```
+/// use tcn::*;
+///
+/// let rak = ReportAuthorizationKey::new(rand::thread_rng());
+/// let mut tck = rak.initial_temporary_contact_key();
+/// let mut tcns = Vec::new();
+/// for _ in 0..10 {
+///     tcns.push(tck.temporary_contact_number());
+///     tck = tck.ratchet().unwrap();
+/// }
+/// let report = rak
+///     .create_report(MemoType::CoEpiV1, Vec::new(), 5, 10)
+///     .unwrap()
+///     .verify()
+///     .unwrap();
+///
+/// // The server publishes the encrypted set of reported TCNs...
+/// let mut server = PsiServer::new(rand::thread_rng());
+/// server.insert_report(&report);
+/// let published = server.encode_set();
+///
+/// // ...and answers a client's blinded query.
+/// let (client, query) = PsiClient::new(&tcns[..5], rand::thread_rng());
+/// let response = server.evaluate(&query).unwrap();
+/// assert_eq!(client.matches(&response, &published).unwrap(), vec![tcns[4]]);
+/// ```
+pub struct PsiServer {
+    key: Scalar,
+    set: BTreeSet<[u8; 32]>,
+}
+
+impl Drop for PsiServer {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for PsiServer {}
+
+/// Prints only the size of the set, never the secret.
+impl fmt::Debug for PsiServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PsiServer")
+            .field("len", &self.set.len())
+            .field("key", &"<redacted>")
+            .finish()
+    }
+}
+
+impl PsiServer {
+    /// Initialize a new server, with an empty set, from a random number
+    /// generator.
+    pub fn new<R: RngCore + CryptoRng>(mut rng: R) -> PsiServer {
+        PsiServer {
+            key: Scalar::random(&mut rng),
+            set: BTreeSet::new(),
+        }
+    }
+
+    /// Add a temporary contact number to the set.
+    pub fn insert(&mut self, tcn: &TemporaryContactNumber) {
+        self.set
+            .insert((self.key * hash_to_point(tcn)).compress().to_bytes());
+    }
+
+    /// Add every temporary contact number disclosed by a verified report to
+    /// the set.
+    pub fn insert_report(&mut self, report: &Report) {
+        for tcn in report.temporary_contact_numbers() {
+            self.insert(&tcn);
+        }
+    }
+
+    /// Add every temporary contact number disclosed by a verified borrowed
+    /// report to the set.
+    pub fn insert_report_ref(&mut self, report: &ReportRef<'_>) {
+        for tcn in report.temporary_contact_numbers() {
+            self.insert(&tcn);
+        }
+    }
+
+    /// Get the number of distinct temporary contact numbers in the set.
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Check whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Encode the set for publication, as the concatenation of its 32-byte
+    /// elements in sorted order.
+    ///
+    /// The order of the elements is unrelated to the reports and positions
+    /// of the temporary contact numbers.
+    pub fn encode_set(&self) -> Vec<u8> {
+        self.set.iter().flatten().copied().collect()
+    }
+
+    /// Answer a client's blinded query.
+    pub fn evaluate(&self, query: &PsiQuery) -> Result<PsiResponse, Error> {
+        let elements = query
+            .elements
+            .iter()
+            .map(|element| Ok((self.key * decompress(element)?).compress().to_bytes()))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(PsiResponse { elements })
+    }
+}
+
+/// The client side of private set intersection matching.
+///
+/// See [`PsiServer`] for a description of the protocol.  The blinding scalar
+/// is zeroized when this value is dropped.
+pub struct PsiClient {
+    key: Scalar,
+    observations: Vec<TemporaryContactNumber>,
+}
+
+impl Drop for PsiClient {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for PsiClient {}
+
+/// Prints only the number of observations, never the secret.
+impl fmt::Debug for PsiClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PsiClient")
+            .field("len", &self.observations.len())
+            .field("key", &"<redacted>")
+            .finish()
+    }
+}
+
+impl PsiClient {
+    /// Blind a client's observed temporary contact numbers, returning the
+    /// client state and the query to send to the server.
+    pub fn new<R: RngCore + CryptoRng>(
+        observations: &[TemporaryContactNumber],
+        mut rng: R,
+    ) -> (PsiClient, PsiQuery) {
+        let key = Scalar::random(&mut rng);
+        let elements = observations
+            .iter()
+            .map(|tcn| (key * hash_to_point(tcn)).compress().to_bytes())
+            .collect();
+        (
+            PsiClient {
+                key,
+                observations: observations.to_vec(),
+            },
+            PsiQuery { elements },
+        )
+    }
+
+    /// Unblind the server's response and compare it with the published set,
+    /// returning the observations that were reported.
+    pub fn matches(
+        &self,
+        response: &PsiResponse,
+        published: &[u8],
+    ) -> Result<Vec<TemporaryContactNumber>, Error> {
+        if response.elements.len() != self.observations.len() {
+            return Err(Error::InvalidPsiMessage);
+        }
+        if !published.len().is_multiple_of(PSI_ELEMENT_LEN) {
+            return Err(Error::InvalidLength(published.len()));
+        }
+
+        let unblind = self.key.invert();
+        let unblinded = response
+            .elements
+            .iter()
+            .map(|element| Ok((unblind * decompress(element)?).compress().to_bytes()))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let wanted = unblinded.iter().copied().collect::<BTreeSet<_>>();
+        let found = published
+            .chunks_exact(PSI_ELEMENT_LEN)
+            .filter(|element| wanted.contains(*element))
+            .map(|element| element.try_into().expect("chunk has length 32"))
+            .collect::<BTreeSet<[u8; 32]>>();
+
+        Ok(self
+            .observations
+            .iter()
+            .zip(unblinded.iter())
+            .filter(|(_, element)| found.contains(*element))
+            .map(|(tcn, _)| *tcn)
+            .collect())
+    }
+
+    /// Check whether any of the observations were reported.
+    pub fn intersects(&self, response: &PsiResponse, published: &[u8]) -> Result<bool, Error> {
+        Ok(!self.matches(response, published)?.is_empty())
+    }
+}
+
+/// A client's blinded observations, sent to a [`PsiServer`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PsiQuery {
+    pub(crate) elements: Vec<[u8; 32]>,
+}
+
+/// A [`PsiServer`]'s answer to a [`PsiQuery`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PsiResponse {
+    pub(crate) elements: Vec<[u8; 32]>,
+}
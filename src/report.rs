@@ -1,6 +1,11 @@
-use std::convert::TryFrom;
+use core::{convert::TryInto, ops::RangeInclusive};
 
-pub use super::{Error, ReportAuthorizationKey, TemporaryContactKey, TemporaryContactNumber};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+pub use super::{
+    CipherSuite, CipherSuiteId, Error, ReportAuthorizationKey, TemporaryContactNumber,
+};
 
 /// Describes the intended type of the contents of a memo field.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -14,10 +19,39 @@ pub enum MemoType {
     Reserved = 0xff,
 }
 
+/// The version of a report's wire format.
+///
+/// Version 0 is the original, unversioned format, which every client can
+/// parse.  Later versions are carried in an envelope that legacy clients
+/// reject as an unknown memo type, rather than misparse, so that a fleet can
+/// keep creating version 0 reports until every client has been upgraded.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(u8)]
+pub enum ReportVersion {
+    /// The original, unversioned format.
+    V0 = 0,
+    /// The first versioned format, which identifies its version in the signed
+    /// report.
+    V1 = 1,
+    /// Identifies the report's [`CipherSuite`] after its version.
+    V2 = 2,
+    /// Extends the memo length to two bytes, allowing memos of up to 65535
+    /// bytes.
+    V3 = 3,
+}
+
+impl ReportVersion {
+    /// The latest report version.
+    pub const LATEST: ReportVersion = ReportVersion::V3;
+}
+
 /// A report of potential exposure.
+#[cfg(feature = "alloc")]
 #[derive(Clone, Debug)]
 pub struct Report {
-    pub(crate) rvk: ed25519_zebra::PublicKeyBytes,
+    pub(crate) version: ReportVersion,
+    pub(crate) suite: CipherSuiteId,
+    pub(crate) rvk: ed25519_zebra::VerificationKeyBytes,
     pub(crate) tck_bytes: [u8; 32],
     // Invariant: j_1 > 0.
     pub(crate) j_1: u16,
@@ -26,7 +60,18 @@ pub struct Report {
     pub(crate) memo_data: Vec<u8>,
 }
 
+#[cfg(feature = "alloc")]
 impl Report {
+    /// Get the wire format version of the report.
+    pub fn version(&self) -> ReportVersion {
+        self.version
+    }
+
+    /// Get the cipher suite of the report.
+    pub fn suite(&self) -> CipherSuiteId {
+        self.suite
+    }
+
     /// Get the type of the memo field.
     pub fn memo_type(&self) -> MemoType {
         self.memo_type
@@ -37,26 +82,153 @@ impl Report {
         &self.memo_data
     }
 
+    /// Get the indices `j_1..=j_2` of the temporary contact numbers included
+    /// in the report.
+    pub fn index_range(&self) -> RangeInclusive<u16> {
+        self.j_1..=self.j_2
+    }
+
+    /// Get the report verification key, which identifies the report
+    /// authorization key that signed the report.
+    pub fn verification_key_bytes(&self) -> [u8; 32] {
+        self.rvk.into()
+    }
+
     /// Return an iterator over all temporary contact numbers included in the report.
     pub fn temporary_contact_numbers(&self) -> impl Iterator<Item = TemporaryContactNumber> {
-        let mut tck = TemporaryContactKey {
-            // Does not underflow as j_1 > 0.
-            index: self.j_1 - 1,
-            rvk: self.rvk,
-            tck_bytes: self.tck_bytes,
-        };
-        // Ratchet to obtain tck_{j_1}.
-        tck = tck.ratchet().expect("j_1 - 1 < j_1 <= u16::MAX");
-
-        (self.j_1..=self.j_2).map(move |j| {
-            let tcn = tck.temporary_contact_number();
-            if j < u16::MAX {
-                tck = tck
-                    .ratchet()
-                    .expect("we do not ratchet past j_2 <= u16::MAX");
-            }
-            tcn
-        })
+        temporary_contact_numbers(
+            self.suite,
+            self.rvk.into(),
+            self.tck_bytes,
+            self.j_1,
+            self.j_2,
+        )
+    }
+
+    /// Borrow this report as a `ReportRef`.
+    pub(crate) fn as_report_ref(&self) -> ReportRef<'_> {
+        ReportRef {
+            version: self.version,
+            suite: self.suite,
+            rvk: self.rvk.as_ref().try_into().expect("rvk has length 32"),
+            tck_bytes: &self.tck_bytes,
+            j_1: self.j_1,
+            j_2: self.j_2,
+            memo_type: self.memo_type,
+            memo_data: &self.memo_data,
+        }
+    }
+}
+
+/// Expand the temporary contact numbers `tcn_{j_1}..=tcn_{j_2}` from `tck_{j_1 - 1}`.
+fn temporary_contact_numbers(
+    suite: CipherSuiteId,
+    rvk: [u8; 32],
+    tck_bytes: [u8; 32],
+    j_1: u16,
+    j_2: u16,
+) -> impl Iterator<Item = TemporaryContactNumber> {
+    // Ratchet to obtain tck_{j_1}.
+    let mut tck_bytes = suite.hash_tck(&rvk, &tck_bytes);
+
+    (j_1..=j_2).map(move |index| {
+        let tcn = TemporaryContactNumber(suite.hash_tcn(index, &tck_bytes));
+        if index < j_2 {
+            tck_bytes = suite.hash_tck(&rvk, &tck_bytes);
+        }
+        tcn
+    })
+}
+
+/// The oldest report version that identifies the cipher suite and can carry
+/// a memo of `memo_len` bytes.
+fn suite_report_version(memo_len: usize) -> ReportVersion {
+    if memo_len > ReportVersion::V2.max_memo_len() {
+        ReportVersion::V3
+    } else {
+        ReportVersion::V2
+    }
+}
+
+/// A borrowed view of a report of potential exposure.
+///
+/// Unlike [`Report`], a `ReportRef` is parsed directly from a byte slice
+/// without allocating, which makes it suitable for scanning large batches of
+/// downloaded (or memory-mapped) reports.  Use [`ReportRef::to_report`] to
+/// obtain an owned `Report` when one is needed.
+#[derive(Copy, Clone, Debug)]
+pub struct ReportRef<'a> {
+    pub(crate) version: ReportVersion,
+    pub(crate) suite: CipherSuiteId,
+    pub(crate) rvk: &'a [u8; 32],
+    pub(crate) tck_bytes: &'a [u8; 32],
+    // Invariant: j_1 > 0.
+    pub(crate) j_1: u16,
+    pub(crate) j_2: u16,
+    pub(crate) memo_type: MemoType,
+    pub(crate) memo_data: &'a [u8],
+}
+
+impl<'a> ReportRef<'a> {
+    /// Get the wire format version of the report.
+    pub fn version(&self) -> ReportVersion {
+        self.version
+    }
+
+    /// Get the cipher suite of the report.
+    pub fn suite(&self) -> CipherSuiteId {
+        self.suite
+    }
+
+    /// Get the type of the memo field.
+    pub fn memo_type(&self) -> MemoType {
+        self.memo_type
+    }
+
+    /// Get the memo data.
+    pub fn memo_data(&self) -> &'a [u8] {
+        self.memo_data
+    }
+
+    /// Get the indices `j_1..=j_2` of the temporary contact numbers included
+    /// in the report.
+    pub fn index_range(&self) -> RangeInclusive<u16> {
+        self.j_1..=self.j_2
+    }
+
+    /// Get the report verification key, which identifies the report
+    /// authorization key that signed the report.
+    pub fn verification_key_bytes(&self) -> &'a [u8; 32] {
+        self.rvk
+    }
+
+    /// Return an iterator over all temporary contact numbers included in the report.
+    pub fn temporary_contact_numbers(&self) -> impl Iterator<Item = TemporaryContactNumber> {
+        temporary_contact_numbers(self.suite, *self.rvk, *self.tck_bytes, self.j_1, self.j_2)
+    }
+
+    /// Copy this view into an owned `Report`.
+    #[cfg(feature = "alloc")]
+    pub fn to_report(&self) -> Report {
+        Report {
+            version: self.version,
+            suite: self.suite,
+            rvk: (*self.rvk).into(),
+            tck_bytes: *self.tck_bytes,
+            j_1: self.j_1,
+            j_2: self.j_2,
+            memo_type: self.memo_type,
+            memo_data: self.memo_data.to_vec(),
+        }
+    }
+}
+
+/// Reject the reserved memo type, which would be read back as the version
+/// escape rather than as a memo type.
+fn check_memo_type(memo_type: MemoType) -> Result<(), Error> {
+    match memo_type {
+        MemoType::Reserved => Err(Error::UnknownMemoType(memo_type as u8)),
+        _ => Ok(()),
     }
 }
 
@@ -74,11 +246,18 @@ impl ReportAuthorizationKey {
     /// Creating a report reveals *all* temporary contact numbers subsequent to
     /// `j_1`, not just up to `j_2`, which is included for convenience.
     ///
-    /// The `memo_data` must be less than 256 bytes long.
+    /// The `memo_data` must be less than 256 bytes long, except in version 3
+    /// reports, which allow memos of up to 65535 bytes.  The `memo_type` must
+    /// not be [`MemoType::Reserved`], whose value is the version escape.
     ///
     /// Reports are unlinkable from each other **only up to the memo field**. In
     /// other words, adding the same high-entropy data to the memo fields of
     /// multiple reports will cause them to be linkable.
+    ///
+    /// The report uses the version 0 wire format, which every client can parse.
+    /// Use [`create_versioned_report`](ReportAuthorizationKey::create_versioned_report)
+    /// to choose another version.
+    #[cfg(feature = "alloc")]
     pub fn create_report(
         &self,
         memo_type: MemoType,
@@ -86,20 +265,96 @@ impl ReportAuthorizationKey {
         j_1: u16,
         j_2: u16,
     ) -> Result<SignedReport, Error> {
-        // Ensure that j_1 is at least 1.
-        let j_1 = if j_1 == 0 { 1 } else { j_1 };
+        self.create_versioned_report(ReportVersion::V0, memo_type, memo_data, j_1, j_2)
+    }
 
-        // Recompute tck_{j_1-1}. This requires recomputing j_1-1 hashes, but
-        // creating reports is done infrequently and it means we don't force the
-        // caller to have saved all intermediate hashes.
-        let mut tck = self.tck_0();
-        for _ in 0..(j_1 - 1) {
-            tck = tck.ratchet().expect("j_1 - 1 < u16::MAX");
+    /// Create a report of potential exposure with the given wire format
+    /// version.
+    ///
+    /// The other inputs and notes are the same as for
+    /// [`create_report`](ReportAuthorizationKey::create_report).  Only clients
+    /// that support `version` can parse the report.
+    #[cfg(feature = "alloc")]
+    pub fn create_versioned_report(
+        &self,
+        version: ReportVersion,
+        memo_type: MemoType,
+        memo_data: Vec<u8>,
+        j_1: u16,
+        j_2: u16,
+    ) -> Result<SignedReport, Error> {
+        self.create_report_with(
+            version,
+            CipherSuiteId::Sha256Ed25519,
+            memo_type,
+            memo_data,
+            j_1,
+            j_2,
+        )
+    }
+
+    /// Create a report of potential exposure about TCNs derived with the
+    /// cipher suite `S`.
+    ///
+    /// The other inputs and notes are the same as for
+    /// [`create_report`](ReportAuthorizationKey::create_report).  The report
+    /// uses the version 2 wire format, which identifies the suite, so that
+    /// verifiers expand it with the right hash function, or the version 3
+    /// format if the memo data is longer than 255 bytes.
+    #[cfg(feature = "alloc")]
+    pub fn create_suite_report<S: CipherSuite>(
+        &self,
+        memo_type: MemoType,
+        memo_data: Vec<u8>,
+        j_1: u16,
+        j_2: u16,
+    ) -> Result<SignedReport, Error> {
+        let version = suite_report_version(memo_data.len());
+        self.create_report_with(version, S::ID, memo_type, memo_data, j_1, j_2)
+    }
+
+    /// Create a report of potential exposure about TCNs derived with the
+    /// cipher suite `S`, with the given wire format version.
+    ///
+    /// The other inputs and notes are the same as for
+    /// [`create_suite_report`](ReportAuthorizationKey::create_suite_report).
+    /// Versions 0 and 1 do not identify the suite, so they can only be used
+    /// with [`Sha256Ed25519`](crate::Sha256Ed25519); other suites fail with
+    /// [`Error::UnsupportedCipherSuite`].
+    #[cfg(feature = "alloc")]
+    pub fn create_versioned_suite_report<S: CipherSuite>(
+        &self,
+        version: ReportVersion,
+        memo_type: MemoType,
+        memo_data: Vec<u8>,
+        j_1: u16,
+        j_2: u16,
+    ) -> Result<SignedReport, Error> {
+        if !version.has_suite() && S::ID != CipherSuiteId::Sha256Ed25519 {
+            return Err(Error::UnsupportedCipherSuite(S::ID as u8));
         }
+        self.create_report_with(version, S::ID, memo_type, memo_data, j_1, j_2)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn create_report_with(
+        &self,
+        version: ReportVersion,
+        suite: CipherSuiteId,
+        memo_type: MemoType,
+        memo_data: Vec<u8>,
+        j_1: u16,
+        j_2: u16,
+    ) -> Result<SignedReport, Error> {
+        check_memo_type(memo_type)?;
+        // Ensure that j_1 is at least 1.
+        let j_1 = if j_1 == 0 { 1 } else { j_1 };
 
         let report = Report {
-            rvk: ed25519_zebra::PublicKeyBytes::from(&self.rak),
-            tck_bytes: tck.tck_bytes,
+            version,
+            suite,
+            rvk: ed25519_zebra::VerificationKeyBytes::from(&self.rak),
+            tck_bytes: self.tck_bytes_before(suite, j_1),
             // Invariant: we have ensured j_1 > 0 above.
             j_1,
             j_2,
@@ -107,16 +362,152 @@ impl ReportAuthorizationKey {
             memo_data,
         };
 
-        use std::io::Cursor;
-        let mut report_bytes = Vec::with_capacity(report.size_hint());
-        report.write(Cursor::new(&mut report_bytes))?;
-        let sig = self.rak.sign(&report_bytes);
+        let sig = suite.sign(self, &report.to_vec()?);
 
-        Ok(SignedReport { report, sig })
+        Ok(SignedReport {
+            report,
+            sig: ed25519_zebra::Signature::from_bytes(&sig),
+        })
+    }
+
+    /// Create a report of potential exposure, serializing it into `out`
+    /// without allocating.
+    ///
+    /// The inputs and notes are the same as for
+    /// [`create_report`](ReportAuthorizationKey::create_report).  The buffer
+    /// must have room for the signed report, which is 134 bytes plus the length
+    /// of the memo data.  On success, returns a view of the signed report
+    /// borrowed from the start of `out`.
+    pub fn create_report_into<'a>(
+        &self,
+        memo_type: MemoType,
+        memo_data: &[u8],
+        j_1: u16,
+        j_2: u16,
+        out: &'a mut [u8],
+    ) -> Result<SignedReportRef<'a>, Error> {
+        self.create_versioned_report_into(ReportVersion::V0, memo_type, memo_data, j_1, j_2, out)
+    }
+
+    /// Create a report of potential exposure with the given wire format
+    /// version, serializing it into `out` without allocating.
+    ///
+    /// The inputs and notes are the same as for
+    /// [`create_versioned_report`](ReportAuthorizationKey::create_versioned_report).
+    /// The buffer must have room for the signed report, which is 134 bytes for
+    /// version 0, 136 bytes for version 1, 137 bytes for version 2 and 138
+    /// bytes for version 3, plus the length of the memo data.
+    pub fn create_versioned_report_into<'a>(
+        &self,
+        version: ReportVersion,
+        memo_type: MemoType,
+        memo_data: &[u8],
+        j_1: u16,
+        j_2: u16,
+        out: &'a mut [u8],
+    ) -> Result<SignedReportRef<'a>, Error> {
+        self.create_report_into_with(
+            version,
+            CipherSuiteId::Sha256Ed25519,
+            memo_type,
+            memo_data,
+            j_1,
+            j_2,
+            out,
+        )
+    }
+
+    /// Create a report of potential exposure about TCNs derived with the
+    /// cipher suite `S`, serializing it into `out` without allocating.
+    ///
+    /// The inputs and notes are the same as for
+    /// [`create_suite_report`](ReportAuthorizationKey::create_suite_report).
+    /// The buffer must have room for the signed report, which is 137 bytes, or
+    /// 138 bytes for memos longer than 255 bytes, plus the length of the memo
+    /// data.
+    pub fn create_suite_report_into<'a, S: CipherSuite>(
+        &self,
+        memo_type: MemoType,
+        memo_data: &[u8],
+        j_1: u16,
+        j_2: u16,
+        out: &'a mut [u8],
+    ) -> Result<SignedReportRef<'a>, Error> {
+        self.create_report_into_with(
+            suite_report_version(memo_data.len()),
+            S::ID,
+            memo_type,
+            memo_data,
+            j_1,
+            j_2,
+            out,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_report_into_with<'a>(
+        &self,
+        version: ReportVersion,
+        suite: CipherSuiteId,
+        memo_type: MemoType,
+        memo_data: &[u8],
+        j_1: u16,
+        j_2: u16,
+        out: &'a mut [u8],
+    ) -> Result<SignedReportRef<'a>, Error> {
+        check_memo_type(memo_type)?;
+        // Ensure that j_1 is at least 1.
+        let j_1 = if j_1 == 0 { 1 } else { j_1 };
+
+        let rvk = <[u8; 32]>::from(ed25519_zebra::VerificationKeyBytes::from(&self.rak));
+        let tck_bytes = self.tck_bytes_before(suite, j_1);
+        let report = ReportRef {
+            version,
+            suite,
+            rvk: &rvk,
+            tck_bytes: &tck_bytes,
+            // Invariant: we have ensured j_1 > 0 above.
+            j_1,
+            j_2,
+            memo_type,
+            memo_data,
+        };
+
+        // Report an oversize memo before asking for a larger buffer.
+        let memo_len = memo_data.len();
+        if memo_len > version.max_memo_len() {
+            return Err(Error::OversizeMemo(memo_len));
+        }
+        let signed_len = report.encoded_len() + 64;
+        if out.len() < signed_len {
+            return Err(Error::BufferTooSmall(signed_len));
+        }
+        let report_len = report.encode_to_slice(out)?;
+        let sig = suite.sign(self, &out[..report_len]);
+        out[report_len..signed_len].copy_from_slice(&sig);
+
+        let (signed_report, _) = SignedReportRef::parse(&out[..signed_len])?;
+        Ok(signed_report)
+    }
+
+    /// Recompute the bytes of `tck_{j_1 - 1}` for a report starting at
+    /// `j_1 > 0`.
+    fn tck_bytes_before(&self, suite: CipherSuiteId, j_1: u16) -> [u8; 32] {
+        // This requires recomputing j_1-1 hashes, but creating reports is done
+        // infrequently and it means we don't force the caller to have saved
+        // all intermediate hashes.
+        let rvk = <[u8; 32]>::from(ed25519_zebra::VerificationKeyBytes::from(&self.rak));
+        let mut tck_bytes =
+            suite.hash_tck_0(self.rak.as_ref().try_into().expect("rak has length 32"));
+        for _ in 0..(j_1 - 1) {
+            tck_bytes = suite.hash_tck(&rvk, &tck_bytes);
+        }
+        tck_bytes
     }
 }
 
 /// A signed exposure report, whose source integrity can be verified to produce a `Report`.
+#[cfg(feature = "alloc")]
 #[derive(Clone, Debug)]
 pub struct SignedReport {
     pub(crate) report: Report,
@@ -124,18 +515,45 @@ pub struct SignedReport {
     pub sig: ed25519_zebra::Signature,
 }
 
+#[cfg(feature = "alloc")]
 impl SignedReport {
     /// Verify the source integrity of this report, producing `Ok(Report)` if successful.
     pub fn verify(self) -> Result<Report, Error> {
-        use std::io::Cursor;
-        let mut report_bytes = Vec::with_capacity(self.report.size_hint());
-        self.report.write(Cursor::new(&mut report_bytes))?;
-
-        match ed25519_zebra::PublicKey::try_from(self.report.rvk)
-            .and_then(|pk| pk.verify(&self.sig, &report_bytes))
-        {
-            Ok(_) => Ok(self.report),
-            Err(_) => Err(Error::ReportVerificationFailed),
+        let report_bytes = self.report.to_vec()?;
+        self.report
+            .suite
+            .verify(&self.report.rvk.into(), &self.sig.to_bytes(), &report_bytes)?;
+        Ok(self.report)
+    }
+}
+
+/// A borrowed view of a signed exposure report.
+///
+/// This is the zero-copy counterpart of [`SignedReport`]: parsing and
+/// verification operate directly on the underlying byte slice.
+#[derive(Copy, Clone, Debug)]
+pub struct SignedReportRef<'a> {
+    pub(crate) report: ReportRef<'a>,
+    // The serialized report covered by the signature.
+    pub(crate) report_bytes: &'a [u8],
+    pub(crate) sig: &'a [u8; 64],
+}
+
+impl<'a> SignedReportRef<'a> {
+    /// Verify the source integrity of this report, producing `Ok(ReportRef)` if successful.
+    pub fn verify(&self) -> Result<ReportRef<'a>, Error> {
+        self.report
+            .suite
+            .verify(self.report.rvk, self.sig, self.report_bytes)?;
+        Ok(self.report)
+    }
+
+    /// Copy this view into an owned `SignedReport`.
+    #[cfg(feature = "alloc")]
+    pub fn to_signed_report(&self) -> SignedReport {
+        SignedReport {
+            report: self.report.to_report(),
+            sig: ed25519_zebra::Signature::from_bytes(self.sig),
         }
     }
 }
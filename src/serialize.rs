@@ -1,13 +1,85 @@
-use std::{
+use core::{
     convert::{TryFrom, TryInto},
-    io::{self, Read},
+    marker::PhantomData,
 };
+#[cfg(feature = "std")]
+use std::io;
 
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "std")]
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 
-use super::{Error, MemoType, Report, ReportAuthorizationKey, SignedReport, TemporaryContactKey};
+use super::{
+    authority::{TOKEN_ID_LEN, TOKEN_LEN},
+    share::{SHARE_TAG_LEN, TCN_SHARE_LEN},
+    CipherSuiteId, Error, HealthAuthorityKey, MasterSeed, MemoType, PublisherKey,
+    ReportAuthorizationKey, ReportRef, ReportVersion, SignedReportRef, TcnShare,
+    TemporaryContactKey, VerificationToken,
+};
+#[cfg(feature = "blind-auth")]
+use super::{
+    blind::BLIND_AUTHORIZATION_LEN, BlindAuthorityKey, BlindAuthorization, BlindRequest,
+    BlindResponse,
+};
+#[cfg(feature = "psi")]
+use super::{psi::PSI_ELEMENT_LEN, PsiQuery, PsiResponse};
+#[cfg(feature = "alloc")]
+use super::{Report, SignedReport, TcnList, TemporaryContactNumber};
+
+/// The length of a serialized version 0 report, excluding the memo data.
+const REPORT_HEADER_LEN: usize = 32 + 32 + 2 + 2 + 1 + 1;
+
+/// The offset of the memo type of a version 0 report, where versioned reports
+/// instead have [`VERSION_ESCAPE`].
+const MEMO_TYPE_OFFSET: usize = 32 + 32 + 2 + 2;
+
+/// Marks a versioned report envelope.  This is the reserved memo type, which
+/// legacy clients reject.
+const VERSION_ESCAPE: u8 = MemoType::Reserved as u8;
+
+impl ReportVersion {
+    /// The length of a serialized report of this version, excluding the memo
+    /// data.
+    pub(crate) fn header_len(self) -> usize {
+        match self {
+            ReportVersion::V0 => REPORT_HEADER_LEN,
+            // The escape and version bytes precede the memo type.
+            ReportVersion::V1 => REPORT_HEADER_LEN + 2,
+            // As does the cipher suite.
+            ReportVersion::V2 => REPORT_HEADER_LEN + 3,
+            // And the memo length has a second byte.
+            ReportVersion::V3 => REPORT_HEADER_LEN + 4,
+        }
+    }
+
+    /// Whether the envelope identifies the report's cipher suite.
+    pub(crate) fn has_suite(self) -> bool {
+        self >= ReportVersion::V2
+    }
+
+    /// The length of the memo length field.
+    fn memo_len_width(self) -> usize {
+        match self {
+            ReportVersion::V3 => 2,
+            _ => 1,
+        }
+    }
+
+    /// The longest memo that a report of this version can carry.
+    pub(crate) fn max_memo_len(self) -> usize {
+        match self {
+            ReportVersion::V3 => u16::MAX as usize,
+            _ => u8::MAX as usize,
+        }
+    }
+}
+
+/// The length of a serialized temporary contact key.
+const TCK_LEN: usize = 2 + 32 + 32;
 
 /// Some convenience methods to add to Read.
+#[cfg(feature = "std")]
 trait ReadExt: io::Read + Sized {
     /// Convenience method to read a `[u8; 32]`.
     #[inline]
@@ -25,18 +97,27 @@ trait ReadExt: io::Read + Sized {
         Ok(bytes)
     }
 
-    /// Convenience method to read a short vector with a 1-byte length tag.
+    /// Convenience method to read a memo with a 1- or 2-byte length tag.
     #[inline]
-    fn read_compact_vec(&mut self) -> io::Result<Vec<u8>> {
-        let len = self.read_u8()? as usize;
-        let mut bytes = Vec::with_capacity(len);
-        self.take(len as u64).read_to_end(&mut bytes)?;
+    fn read_memo(&mut self, version: ReportVersion) -> io::Result<Vec<u8>> {
+        let len = match version.memo_len_width() {
+            1 => self.read_u8()? as usize,
+            _ => self.read_u16::<LittleEndian>()? as usize,
+        };
+        let mut bytes = vec![0; len];
+        self.read_exact(&mut bytes)?;
         Ok(bytes)
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: io::Read> ReadExt for R {}
 
+/// Borrow the first `len` bytes of `out`, failing if it is too short.
+fn split_output(out: &mut [u8], len: usize) -> Result<&mut [u8], Error> {
+    out.get_mut(..len).ok_or(Error::BufferTooSmall(len))
+}
+
 impl TryFrom<u8> for MemoType {
     type Error = Error;
 
@@ -49,21 +130,93 @@ impl TryFrom<u8> for MemoType {
     }
 }
 
+impl TryFrom<u8> for CipherSuiteId {
+    type Error = Error;
+
+    fn try_from(id: u8) -> Result<CipherSuiteId, Self::Error> {
+        match id {
+            0 => Ok(CipherSuiteId::Sha256Ed25519),
+            1 => Ok(CipherSuiteId::Blake3Ed25519),
+            id => Err(Error::UnsupportedCipherSuite(id)),
+        }
+    }
+}
+
+/// Parses the version byte of a versioned report envelope.
+impl TryFrom<u8> for ReportVersion {
+    type Error = Error;
+
+    fn try_from(v: u8) -> Result<ReportVersion, Self::Error> {
+        match v {
+            // Version 0 reports are never enveloped.
+            1 => Ok(ReportVersion::V1),
+            2 => Ok(ReportVersion::V2),
+            3 => Ok(ReportVersion::V3),
+            v => Err(Error::UnsupportedReportVersion(v)),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl Report {
     /// Compute the size of the serialization, to allow preallocations.
     pub(crate) fn size_hint(&self) -> usize {
-        32 + 32 + 2 + 2 + 1 + 1 + self.memo_data.len()
+        self.version.header_len() + self.memo_data.len()
+    }
+
+    /// Serialize this report into a new vector.
+    pub(crate) fn to_vec(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = vec![0; self.size_hint()];
+        self.encode_to_slice(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Try to decode a `Report` from a byte slice, which must contain exactly
+    /// one serialized report.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<Report, Error> {
+        match ReportRef::parse(bytes)? {
+            (report, []) => Ok(report.to_report()),
+            _ => Err(Error::InvalidLength(bytes.len())),
+        }
+    }
+
+    /// Try to encode a `Report` into the start of a byte slice, returning the
+    /// number of bytes written.
+    ///
+    /// This method fails only when the memo data is too long or the slice is
+    /// too short.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        self.as_report_ref().encode_to_slice(out)
     }
 
     /// Try to read a `Report` from a generic `io::Read`er.
-    pub fn read<R: std::io::Read>(mut reader: R) -> Result<Report, Error> {
+    #[cfg(feature = "std")]
+    pub fn read<R: io::Read>(mut reader: R) -> Result<Report, Error> {
+        let rvk = reader.read_32_bytes()?.into();
+        let tck_bytes = reader.read_32_bytes()?;
+        let j_1 = reader.read_u16::<LittleEndian>()?;
+        let j_2 = reader.read_u16::<LittleEndian>()?;
+        let (version, suite, memo_type) = match reader.read_u8()? {
+            VERSION_ESCAPE => {
+                let version: ReportVersion = reader.read_u8()?.try_into()?;
+                let suite = if version.has_suite() {
+                    reader.read_u8()?.try_into()?
+                } else {
+                    CipherSuiteId::Sha256Ed25519
+                };
+                (version, suite, reader.read_u8()?)
+            }
+            memo_type => (ReportVersion::V0, CipherSuiteId::Sha256Ed25519, memo_type),
+        };
         let report = Report {
-            rvk: reader.read_32_bytes()?.into(),
-            tck_bytes: reader.read_32_bytes()?,
-            j_1: reader.read_u16::<LittleEndian>()?,
-            j_2: reader.read_u16::<LittleEndian>()?,
-            memo_type: reader.read_u8()?.try_into()?,
-            memo_data: reader.read_compact_vec()?,
+            version,
+            suite,
+            rvk,
+            tck_bytes,
+            j_1,
+            j_2,
+            memo_type: memo_type.try_into()?,
+            memo_data: reader.read_memo(version)?,
         };
 
         // Invariant: j_1 > 0
@@ -78,39 +231,268 @@ impl Report {
     ///
     /// This method fails only when the memo data is too long or in the event of
     /// an underlying I/O error.
+    #[cfg(feature = "std")]
     pub fn write<W: io::Write>(&self, mut writer: W) -> Result<(), Error> {
-        let memo_len = u8::try_from(self.memo_data.len())
-            .map_err(|_| Error::OversizeMemo(self.memo_data.len()))?;
+        let memo_len = self.memo_data.len();
+        if memo_len > self.version.max_memo_len() {
+            return Err(Error::OversizeMemo(memo_len));
+        }
         writer.write_all(&<[u8; 32]>::from(self.rvk))?;
         writer.write_all(&self.tck_bytes)?;
         writer.write_u16::<LittleEndian>(self.j_1)?;
         writer.write_u16::<LittleEndian>(self.j_2)?;
+        if self.version != ReportVersion::V0 {
+            writer.write_all(&[VERSION_ESCAPE, self.version as u8])?;
+        }
+        if self.version.has_suite() {
+            writer.write_u8(self.suite as u8)?;
+        }
         writer.write_u8(self.memo_type as u8)?;
-        writer.write_u8(memo_len)?;
+        match self.version.memo_len_width() {
+            1 => writer.write_u8(memo_len as u8)?,
+            _ => writer.write_u16::<LittleEndian>(memo_len as u16)?,
+        }
         writer.write_all(&self.memo_data)?;
         Ok(())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl SignedReport {
+    /// Compute the size of the serialization, to allow preallocations.
+    pub(crate) fn size_hint(&self) -> usize {
+        self.report.size_hint() + 64
+    }
+
+    /// Serialize this signed report into a new vector.
+    pub(crate) fn to_vec(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = vec![0; self.size_hint()];
+        self.encode_to_slice(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Try to decode a `SignedReport` from a byte slice, which must contain
+    /// exactly one serialized signed report.
+    ///
+    /// This does not verify the report's signature.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<SignedReport, Error> {
+        match SignedReportRef::parse(bytes)? {
+            (signed_report, []) => Ok(signed_report.to_signed_report()),
+            _ => Err(Error::InvalidLength(bytes.len())),
+        }
+    }
+
+    /// Try to encode a `SignedReport` into the start of a byte slice,
+    /// returning the number of bytes written.
+    ///
+    /// This method fails only when the memo data is too long or the slice is
+    /// too short.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = split_output(out, self.size_hint())?;
+        let report_len = self.report.encode_to_slice(out)?;
+        out[report_len..].copy_from_slice(&self.sig.to_bytes());
+        Ok(out.len())
+    }
+
     /// Try to read a `SignedReport` from a generic `io::Read`er.
+    #[cfg(feature = "std")]
     pub fn read<R: io::Read>(mut reader: R) -> Result<SignedReport, Error> {
         Ok(SignedReport {
             report: Report::read(&mut reader)?,
-            sig: reader.read_64_bytes()?.into(),
+            sig: ed25519_zebra::Signature::from_bytes(&reader.read_64_bytes()?),
         })
     }
 
     /// Try to write a `SignedReport` into a generic `io::Write`er.
+    #[cfg(feature = "std")]
     pub fn write<W: io::Write>(&self, mut writer: W) -> Result<(), Error> {
         self.report.write(&mut writer)?;
-        writer.write_all(&<[u8; 64]>::from(self.sig)[..])?;
+        writer.write_all(&self.sig.to_bytes())?;
         Ok(())
     }
 }
 
+impl<'a> ReportRef<'a> {
+    /// Compute the size of the serialization.
+    pub(crate) fn encoded_len(&self) -> usize {
+        self.version.header_len() + self.memo_data.len()
+    }
+
+    /// Try to parse a `ReportRef` from the start of `bytes`, without copying.
+    ///
+    /// On success, returns the report along with the remaining unparsed bytes.
+    pub fn parse(bytes: &'a [u8]) -> Result<(ReportRef<'a>, &'a [u8]), Error> {
+        if bytes.len() < REPORT_HEADER_LEN {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        let version = match bytes[MEMO_TYPE_OFFSET] {
+            VERSION_ESCAPE => bytes[MEMO_TYPE_OFFSET + 1].try_into()?,
+            _ => ReportVersion::V0,
+        };
+        let header_len = version.header_len();
+        if bytes.len() < header_len {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        let memo_len = match version.memo_len_width() {
+            1 => bytes[header_len - 1] as usize,
+            _ => u16::from_le_bytes([bytes[header_len - 2], bytes[header_len - 1]]) as usize,
+        };
+        let report_len = header_len + memo_len;
+        if bytes.len() < report_len {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        let (report_bytes, rest) = bytes.split_at(report_len);
+
+        let suite = if version.has_suite() {
+            report_bytes[MEMO_TYPE_OFFSET + 2].try_into()?
+        } else {
+            CipherSuiteId::Sha256Ed25519
+        };
+        let report = ReportRef {
+            version,
+            suite,
+            rvk: report_bytes[0..32].try_into().expect("slice has length 32"),
+            tck_bytes: report_bytes[32..64]
+                .try_into()
+                .expect("slice has length 32"),
+            j_1: u16::from_le_bytes([report_bytes[64], report_bytes[65]]),
+            j_2: u16::from_le_bytes([report_bytes[66], report_bytes[67]]),
+            memo_type: report_bytes[header_len - version.memo_len_width() - 1].try_into()?,
+            memo_data: &report_bytes[header_len..],
+        };
+
+        // Invariant: j_1 > 0
+        if report.j_1 > 0 {
+            Ok((report, rest))
+        } else {
+            Err(Error::InvalidReportIndex)
+        }
+    }
+
+    /// Parse a concatenation of reports without signatures, such as a stream
+    /// published by a server that strips signatures, without copying.
+    ///
+    /// The iterator stops after yielding the first parse error.
+    pub fn parse_all(
+        mut bytes: &'a [u8],
+    ) -> impl Iterator<Item = Result<ReportRef<'a>, Error>> + 'a {
+        core::iter::from_fn(move || {
+            if bytes.is_empty() {
+                return None;
+            }
+            match ReportRef::parse(bytes) {
+                Ok((report, rest)) => {
+                    bytes = rest;
+                    Some(Ok(report))
+                }
+                Err(e) => {
+                    bytes = &[];
+                    Some(Err(e))
+                }
+            }
+        })
+    }
+
+    /// Try to encode a `ReportRef` into the start of a byte slice, returning
+    /// the number of bytes written.
+    ///
+    /// This method fails only when the memo data is too long or the slice is
+    /// too short.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let memo_len = self.memo_data.len();
+        if memo_len > self.version.max_memo_len() {
+            return Err(Error::OversizeMemo(memo_len));
+        }
+        let out = split_output(out, self.encoded_len())?;
+        out[0..32].copy_from_slice(self.rvk);
+        out[32..64].copy_from_slice(self.tck_bytes);
+        out[64..66].copy_from_slice(&self.j_1.to_le_bytes());
+        out[66..68].copy_from_slice(&self.j_2.to_le_bytes());
+        let header_len = self.version.header_len();
+        if self.version != ReportVersion::V0 {
+            out[MEMO_TYPE_OFFSET] = VERSION_ESCAPE;
+            out[MEMO_TYPE_OFFSET + 1] = self.version as u8;
+        }
+        if self.version.has_suite() {
+            out[MEMO_TYPE_OFFSET + 2] = self.suite as u8;
+        }
+        let width = self.version.memo_len_width();
+        out[header_len - width - 1] = self.memo_type as u8;
+        out[header_len - width..header_len]
+            .copy_from_slice(&(memo_len as u16).to_le_bytes()[..width]);
+        out[header_len..].copy_from_slice(self.memo_data);
+        Ok(out.len())
+    }
+}
+
+impl<'a> SignedReportRef<'a> {
+    /// Compute the size of the serialization, including the signature.
+    pub fn encoded_len(&self) -> usize {
+        self.report_bytes.len() + 64
+    }
+
+    /// Try to parse a `SignedReportRef` from the start of `bytes`, without copying.
+    ///
+    /// On success, returns the signed report along with the remaining unparsed
+    /// bytes.  This does not verify the report's signature.
+    pub fn parse(bytes: &'a [u8]) -> Result<(SignedReportRef<'a>, &'a [u8]), Error> {
+        let (report, rest) = ReportRef::parse(bytes)?;
+        if rest.len() < 64 {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        let (sig, rest) = rest.split_at(64);
+        Ok((
+            SignedReportRef {
+                report,
+                report_bytes: &bytes[..report.encoded_len()],
+                sig: sig.try_into().expect("slice has length 64"),
+            },
+            rest,
+        ))
+    }
+
+    /// Parse a concatenation of signed reports, such as a downloaded batch,
+    /// without copying.
+    ///
+    /// The iterator stops after yielding the first parse error.
+    pub fn parse_all(
+        mut bytes: &'a [u8],
+    ) -> impl Iterator<Item = Result<SignedReportRef<'a>, Error>> + 'a {
+        core::iter::from_fn(move || {
+            if bytes.is_empty() {
+                return None;
+            }
+            match SignedReportRef::parse(bytes) {
+                Ok((report, rest)) => {
+                    bytes = rest;
+                    Some(Ok(report))
+                }
+                Err(e) => {
+                    bytes = &[];
+                    Some(Err(e))
+                }
+            }
+        })
+    }
+}
+
 impl ReportAuthorizationKey {
+    /// Try to decode a `ReportAuthorizationKey` from a 32-byte slice.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<ReportAuthorizationKey, Error> {
+        let rak = <[u8; 32]>::try_from(bytes).map_err(|_| Error::InvalidLength(bytes.len()))?;
+        Ok(ReportAuthorizationKey { rak: rak.into() })
+    }
+
+    /// Try to encode the **secret** `ReportAuthorizationKey` into the start of
+    /// a byte slice, returning the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = split_output(out, 32)?;
+        out.copy_from_slice(self.rak.as_ref());
+        Ok(out.len())
+    }
+
     /// Try to read a `ReportAuthorizationKey` from a generic `io::Read`er.
+    #[cfg(feature = "std")]
     pub fn read<R: io::Read>(mut reader: R) -> Result<ReportAuthorizationKey, io::Error> {
         Ok(ReportAuthorizationKey {
             rak: reader.read_32_bytes()?.into(),
@@ -118,22 +500,333 @@ impl ReportAuthorizationKey {
     }
 
     /// Try to write a `ReportAuthorizationKey` into a generic `io::Write`er.
+    #[cfg(feature = "std")]
     pub fn write<W: io::Write>(&self, mut writer: W) -> Result<(), io::Error> {
-        writer.write_all(&<[u8; 32]>::from(self.rak))
+        writer.write_all(self.rak.as_ref())
+    }
+}
+
+impl PublisherKey {
+    /// Try to decode a `PublisherKey` from a 32-byte slice.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<PublisherKey, Error> {
+        let sk = <[u8; 32]>::try_from(bytes).map_err(|_| Error::InvalidLength(bytes.len()))?;
+        Ok(PublisherKey { sk: sk.into() })
+    }
+
+    /// Try to encode the **secret** `PublisherKey` into the start of a byte
+    /// slice, returning the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = split_output(out, 32)?;
+        out.copy_from_slice(self.sk.as_ref());
+        Ok(out.len())
+    }
+}
+
+impl HealthAuthorityKey {
+    /// Try to decode a `HealthAuthorityKey` from a 32-byte slice.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<HealthAuthorityKey, Error> {
+        let sk = <[u8; 32]>::try_from(bytes).map_err(|_| Error::InvalidLength(bytes.len()))?;
+        Ok(HealthAuthorityKey { sk: sk.into() })
+    }
+
+    /// Try to encode the **secret** `HealthAuthorityKey` into the start of a
+    /// byte slice, returning the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = split_output(out, 32)?;
+        out.copy_from_slice(self.sk.as_ref());
+        Ok(out.len())
+    }
+}
+
+impl VerificationToken {
+    /// Try to decode a `VerificationToken` from an 80-byte slice.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<VerificationToken, Error> {
+        if bytes.len() != TOKEN_LEN {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        Ok(VerificationToken {
+            id: bytes[..TOKEN_ID_LEN]
+                .try_into()
+                .expect("slice has length 16"),
+            sig: bytes[TOKEN_ID_LEN..]
+                .try_into()
+                .expect("slice has length 64"),
+        })
+    }
+
+    /// Try to encode a `VerificationToken` into the start of a byte slice,
+    /// returning the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = split_output(out, TOKEN_LEN)?;
+        out[..TOKEN_ID_LEN].copy_from_slice(&self.id);
+        out[TOKEN_ID_LEN..].copy_from_slice(&self.sig);
+        Ok(out.len())
+    }
+}
+
+#[cfg(feature = "blind-auth")]
+impl BlindAuthorityKey {
+    /// Try to decode a `BlindAuthorityKey` from a 32-byte slice holding a
+    /// canonically-encoded scalar.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<BlindAuthorityKey, Error> {
+        let sk = <[u8; 32]>::try_from(bytes).map_err(|_| Error::InvalidLength(bytes.len()))?;
+        Option::from(curve25519_dalek::Scalar::from_canonical_bytes(sk))
+            .map(|sk| BlindAuthorityKey { sk })
+            .ok_or(Error::BlindAuthorizationFailed)
+    }
+
+    /// Try to encode the **secret** `BlindAuthorityKey` into the start of a
+    /// byte slice, returning the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = split_output(out, 32)?;
+        out.copy_from_slice(self.sk.as_bytes());
+        Ok(out.len())
+    }
+}
+
+#[cfg(feature = "blind-auth")]
+impl BlindRequest {
+    /// Try to decode a `BlindRequest` from a 32-byte slice.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<BlindRequest, Error> {
+        let point = <[u8; 32]>::try_from(bytes).map_err(|_| Error::InvalidLength(bytes.len()))?;
+        Ok(BlindRequest { point })
+    }
+
+    /// Try to encode a `BlindRequest` into the start of a byte slice,
+    /// returning the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = split_output(out, 32)?;
+        out.copy_from_slice(&self.point);
+        Ok(out.len())
+    }
+}
+
+#[cfg(feature = "blind-auth")]
+impl BlindResponse {
+    /// Try to decode a `BlindResponse` from a 96-byte slice.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<BlindResponse, Error> {
+        if bytes.len() != 96 {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        Ok(BlindResponse {
+            point: bytes[..32].try_into().expect("slice has length 32"),
+            challenge: bytes[32..64].try_into().expect("slice has length 32"),
+            response: bytes[64..].try_into().expect("slice has length 32"),
+        })
+    }
+
+    /// Try to encode a `BlindResponse` into the start of a byte slice,
+    /// returning the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = split_output(out, 96)?;
+        out[..32].copy_from_slice(&self.point);
+        out[32..64].copy_from_slice(&self.challenge);
+        out[64..].copy_from_slice(&self.response);
+        Ok(out.len())
+    }
+}
+
+#[cfg(feature = "blind-auth")]
+impl BlindAuthorization {
+    /// Try to decode a `BlindAuthorization` from a 32-byte slice.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<BlindAuthorization, Error> {
+        let point = <[u8; BLIND_AUTHORIZATION_LEN]>::try_from(bytes)
+            .map_err(|_| Error::InvalidLength(bytes.len()))?;
+        Ok(BlindAuthorization { point })
+    }
+
+    /// Try to encode a `BlindAuthorization` into the start of a byte slice,
+    /// returning the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = split_output(out, BLIND_AUTHORIZATION_LEN)?;
+        out.copy_from_slice(&self.point);
+        Ok(out.len())
+    }
+}
+
+/// Decode a sequence of 32-byte group elements.
+#[cfg(feature = "psi")]
+fn decode_psi_elements(bytes: &[u8]) -> Result<Vec<[u8; 32]>, Error> {
+    if !bytes.len().is_multiple_of(PSI_ELEMENT_LEN) {
+        return Err(Error::InvalidLength(bytes.len()));
+    }
+    Ok(bytes
+        .chunks_exact(PSI_ELEMENT_LEN)
+        .map(|element| element.try_into().expect("chunk has length 32"))
+        .collect())
+}
+
+/// Encode a sequence of 32-byte group elements.
+#[cfg(feature = "psi")]
+fn encode_psi_elements(elements: &[[u8; 32]], out: &mut [u8]) -> Result<usize, Error> {
+    let out = split_output(out, elements.len() * PSI_ELEMENT_LEN)?;
+    for (chunk, element) in out.chunks_exact_mut(PSI_ELEMENT_LEN).zip(elements) {
+        chunk.copy_from_slice(element);
+    }
+    Ok(out.len())
+}
+
+#[cfg(feature = "psi")]
+impl PsiQuery {
+    /// Try to decode a `PsiQuery` from a slice of 32-byte elements.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<PsiQuery, Error> {
+        Ok(PsiQuery {
+            elements: decode_psi_elements(bytes)?,
+        })
+    }
+
+    /// Get the length of the encoding of this query.
+    pub fn encoded_len(&self) -> usize {
+        self.elements.len() * PSI_ELEMENT_LEN
+    }
+
+    /// Try to encode a `PsiQuery` into the start of a byte slice, returning
+    /// the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        encode_psi_elements(&self.elements, out)
+    }
+}
+
+#[cfg(feature = "psi")]
+impl PsiResponse {
+    /// Try to decode a `PsiResponse` from a slice of 32-byte elements.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<PsiResponse, Error> {
+        Ok(PsiResponse {
+            elements: decode_psi_elements(bytes)?,
+        })
+    }
+
+    /// Get the length of the encoding of this response.
+    pub fn encoded_len(&self) -> usize {
+        self.elements.len() * PSI_ELEMENT_LEN
+    }
+
+    /// Try to encode a `PsiResponse` into the start of a byte slice,
+    /// returning the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        encode_psi_elements(&self.elements, out)
+    }
+}
+
+impl TcnShare {
+    /// Try to decode a `TcnShare` from a 22-byte slice.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<TcnShare, Error> {
+        if bytes.len() != TCN_SHARE_LEN {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        Ok(TcnShare {
+            tag: bytes[..SHARE_TAG_LEN]
+                .try_into()
+                .expect("slice has length 4"),
+            threshold: bytes[SHARE_TAG_LEN],
+            index: bytes[SHARE_TAG_LEN + 1],
+            data: bytes[SHARE_TAG_LEN + 2..]
+                .try_into()
+                .expect("slice has length 16"),
+        })
+    }
+
+    /// Try to encode a `TcnShare` into the start of a byte slice, returning
+    /// the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = split_output(out, TCN_SHARE_LEN)?;
+        out[..SHARE_TAG_LEN].copy_from_slice(&self.tag);
+        out[SHARE_TAG_LEN] = self.threshold;
+        out[SHARE_TAG_LEN + 1] = self.index;
+        out[SHARE_TAG_LEN + 2..].copy_from_slice(&self.data);
+        Ok(out.len())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TcnList {
+    /// Try to decode a `TcnList` from a concatenation of 16-byte TCNs.
+    ///
+    /// The list is marked as sorted if the TCNs are in strictly increasing
+    /// order.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<TcnList, Error> {
+        if !bytes.len().is_multiple_of(16) {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        let tcns = bytes
+            .chunks_exact(16)
+            .map(|tcn| TemporaryContactNumber(tcn.try_into().expect("chunk has length 16")))
+            .collect::<Vec<_>>();
+        let sorted = tcns.windows(2).all(|pair| pair[0] < pair[1]);
+        Ok(TcnList { tcns, sorted })
+    }
+
+    /// Get the length of the encoding of this list.
+    pub fn encoded_len(&self) -> usize {
+        self.tcns.len() * 16
+    }
+
+    /// Try to encode a `TcnList` into the start of a byte slice, returning
+    /// the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = split_output(out, self.encoded_len())?;
+        for (chunk, tcn) in out.chunks_exact_mut(16).zip(&self.tcns) {
+            chunk.copy_from_slice(&tcn.0);
+        }
+        Ok(out.len())
+    }
+}
+
+impl MasterSeed {
+    /// Try to decode a `MasterSeed` from a 32-byte slice.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<MasterSeed, Error> {
+        let seed = <[u8; 32]>::try_from(bytes).map_err(|_| Error::InvalidLength(bytes.len()))?;
+        Ok(MasterSeed { seed })
+    }
+
+    /// Try to encode the **secret** `MasterSeed` into the start of a byte
+    /// slice, returning the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = split_output(out, 32)?;
+        out.copy_from_slice(&self.seed);
+        Ok(out.len())
     }
 }
 
 impl TemporaryContactKey {
+    /// Try to decode a `TemporaryContactKey` from a 66-byte slice.
+    pub fn decode_from_slice(bytes: &[u8]) -> Result<TemporaryContactKey, Error> {
+        if bytes.len() != TCK_LEN {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        Ok(TemporaryContactKey {
+            index: u16::from_le_bytes([bytes[0], bytes[1]]),
+            rvk: <[u8; 32]>::try_from(&bytes[2..34])
+                .expect("slice has length 32")
+                .into(),
+            tck_bytes: bytes[34..66].try_into().expect("slice has length 32"),
+            suite: PhantomData,
+        })
+    }
+
+    /// Try to encode a `TemporaryContactKey` into the start of a byte slice,
+    /// returning the number of bytes written.
+    pub fn encode_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = split_output(out, TCK_LEN)?;
+        out[0..2].copy_from_slice(&self.index.to_le_bytes());
+        out[2..34].copy_from_slice(self.rvk.as_ref());
+        out[34..66].copy_from_slice(&self.tck_bytes);
+        Ok(out.len())
+    }
+
     /// Try to read a `TemporaryContactKey` from a generic `io::Read`er.
+    #[cfg(feature = "std")]
     pub fn read<R: io::Read>(mut reader: R) -> Result<TemporaryContactKey, io::Error> {
         Ok(TemporaryContactKey {
             index: reader.read_u16::<LittleEndian>()?,
             rvk: reader.read_32_bytes()?.into(),
             tck_bytes: reader.read_32_bytes()?,
+            suite: PhantomData,
         })
     }
 
     /// Try to write a `TemporaryContactKey` into a generic `io::Write`er.
+    #[cfg(feature = "std")]
     pub fn write<W: io::Write>(&self, mut writer: W) -> Result<(), io::Error> {
         writer.write_u16::<LittleEndian>(self.index)?;
         writer.write_all(&<[u8; 32]>::from(self.rvk))?;
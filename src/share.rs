@@ -0,0 +1,286 @@
+#[cfg(feature = "alloc")]
+use alloc::{collections::VecDeque, vec::Vec};
+use rand_core::{CryptoRng, RngCore};
+
+use super::{Error, TemporaryContactNumber};
+
+/// The length of a share's group tag.
+pub(crate) const SHARE_TAG_LEN: usize = 4;
+/// The length of a serialized [`TcnShare`].
+pub(crate) const TCN_SHARE_LEN: usize = SHARE_TAG_LEN + 1 + 1 + 16;
+
+/// Multiply in GF(2^8) modulo the AES polynomial x^8 + x^4 + x^3 + x + 1,
+/// without secret-dependent branches or table lookups.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (carry & 0x1b);
+        b >>= 1;
+    }
+    product
+}
+
+/// Invert a nonzero element of GF(2^8), as a^254.
+fn gf_inv(a: u8) -> u8 {
+    let mut result = 1;
+    let mut power = a;
+    for bit in 0..8 {
+        if 254 >> bit & 1 == 1 {
+            result = gf_mul(result, power);
+        }
+        power = gf_mul(power, power);
+    }
+    result
+}
+
+/// Evaluate at `x` the Lagrange basis polynomial that is 1 at `j` and 0 at
+/// the other `points`.
+fn lagrange_basis<I: Iterator<Item = u8>>(x: u8, j: u8, points: I) -> u8 {
+    points
+        .filter(|&m| m != j)
+        .fold(1, |basis, m| gf_mul(basis, gf_mul(x ^ m, gf_inv(j ^ m))))
+}
+
+/// One share of a temporary contact number, small enough to broadcast in a
+/// single BLE advertisement.
+///
+/// A TCN split into `n` shares with threshold `k` can be reconstructed from
+/// any `k` of them, while fewer reveal nothing about it.  Broadcasting one
+/// share per advertisement means that a passive observer must receive `k`
+/// advertisements, rather than one, to learn the TCN.  The shares of a TCN
+/// carry the same random tag, so that a receiver can group them.
+///
+/// A share is serialized as
+/// `tag: [u8; 4] || threshold: u8 || index: u8 || data: [u8; 16]`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct TcnShare {
+    pub(crate) tag: [u8; SHARE_TAG_LEN],
+    pub(crate) threshold: u8,
+    pub(crate) index: u8,
+    pub(crate) data: [u8; 16],
+}
+
+impl TcnShare {
+    /// Get the tag shared by all shares of the same TCN.
+    pub fn tag(&self) -> [u8; 4] {
+        self.tag
+    }
+
+    /// Get the number of shares needed to reconstruct the TCN.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Get the index of this share, from 1 to the number of shares.
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+}
+
+impl TemporaryContactNumber {
+    /// Split this TCN into `shares.len()` shares, any `threshold` of which
+    /// reconstruct it, writing them to `shares`.
+    ///
+    /// The threshold must be at least 1 and at most the number of shares,
+    /// which must be at most 255.
+    pub fn split_into<R: RngCore + CryptoRng>(
+        &self,
+        threshold: u8,
+        mut rng: R,
+        shares: &mut [TcnShare],
+    ) -> Result<(), Error> {
+        let count = shares.len();
+        if threshold == 0 || usize::from(threshold) > count || count > 255 {
+            return Err(Error::InvalidShareThreshold(threshold, count));
+        }
+
+        let mut tag = [0; SHARE_TAG_LEN];
+        rng.fill_bytes(&mut tag);
+        // Each byte of the TCN is the value at 0 of a random polynomial of
+        // degree threshold - 1.  Rather than choosing its coefficients, choose
+        // its values at 1, ..., threshold - 1 at random, and interpolate the
+        // other shares through those values and the TCN.
+        let random = usize::from(threshold) - 1;
+        for (i, share) in shares.iter_mut().enumerate() {
+            share.tag = tag;
+            share.threshold = threshold;
+            share.index = i as u8 + 1;
+        }
+        for share in &mut shares[..random] {
+            rng.fill_bytes(&mut share.data);
+        }
+        let (known, rest) = shares.split_at_mut(random);
+        for share in rest {
+            let x = share.index;
+            let points = || 0..threshold;
+            let mut data = [0; 16];
+            let basis = lagrange_basis(x, 0, points());
+            for (out, &y) in data.iter_mut().zip(self.0.iter()) {
+                *out = gf_mul(basis, y);
+            }
+            for point in known.iter() {
+                let basis = lagrange_basis(x, point.index, points());
+                for (out, &y) in data.iter_mut().zip(point.data.iter()) {
+                    *out ^= gf_mul(basis, y);
+                }
+            }
+            share.data = data;
+        }
+        Ok(())
+    }
+
+    /// Split this TCN into `count` shares, any `threshold` of which
+    /// reconstruct it.
+    ///
+    /// ```
+    /// use tcn::*;
+    ///
+    /// let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    /// let tcn = rak.initial_temporary_contact_key().temporary_contact_number();
+    /// let shares = tcn.split(3, 5, rand::thread_rng()).unwrap();
+    ///
+    /// // A receiver reconstructs the TCN from the third share it hears.
+    /// let mut reassembler = ShareReassembler::new(64);
+    /// assert_eq!(reassembler.push(shares[4]), None);
+    /// assert_eq!(reassembler.push(shares[1]), None);
+    /// assert_eq!(reassembler.push(shares[2]), Some(tcn));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn split<R: RngCore + CryptoRng>(
+        &self,
+        threshold: u8,
+        count: u8,
+        rng: R,
+    ) -> Result<Vec<TcnShare>, Error> {
+        let mut shares = alloc::vec![TcnShare::default(); usize::from(count)];
+        self.split_into(threshold, rng, &mut shares)?;
+        Ok(shares)
+    }
+
+    /// Reconstruct a TCN from shares with the same tag and distinct indices.
+    ///
+    /// Only the first `threshold` shares are used.  The shares are not
+    /// authenticated: forged shares reconstruct an unrelated value.
+    pub fn reconstruct(shares: &[TcnShare]) -> Result<TemporaryContactNumber, Error> {
+        let threshold = match shares.first() {
+            Some(share) => share.threshold,
+            None => return Err(Error::InvalidShareThreshold(0, 0)),
+        };
+        if threshold == 0 || usize::from(threshold) > shares.len() {
+            return Err(Error::InvalidShareThreshold(threshold, shares.len()));
+        }
+        let shares = &shares[..usize::from(threshold)];
+        for (i, share) in shares.iter().enumerate() {
+            if share.index == 0
+                || share.tag != shares[0].tag
+                || share.threshold != threshold
+                || shares[..i].iter().any(|other| other.index == share.index)
+            {
+                return Err(Error::InvalidShare(share.index));
+            }
+        }
+
+        // Lagrange interpolation at x = 0, where subtraction is xor.
+        let mut tcn = [0; 16];
+        for share in shares {
+            let basis = lagrange_basis(0, share.index, shares.iter().map(|s| s.index));
+            for (out, &y) in tcn.iter_mut().zip(share.data.iter()) {
+                *out ^= gf_mul(basis, y);
+            }
+        }
+        Ok(TemporaryContactNumber(tcn))
+    }
+}
+
+/// The shares received so far for one tag.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+struct ShareGroup {
+    tag: [u8; SHARE_TAG_LEN],
+    shares: Vec<TcnShare>,
+    complete: bool,
+}
+
+/// Groups received [`TcnShare`]s by tag and reconstructs each TCN once
+/// enough of its shares have arrived.
+///
+/// The reassembler tracks at most a fixed number of tags, forgetting the
+/// oldest when a share with a new tag arrives, so that unrelated or forged
+/// shares cannot exhaust memory.  Tags of reconstructed TCNs are remembered
+/// in the same way, so that each TCN is returned once.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct ShareReassembler {
+    max_groups: usize,
+    groups: VecDeque<ShareGroup>,
+}
+
+#[cfg(feature = "alloc")]
+impl ShareReassembler {
+    /// Create a reassembler tracking at most `max_groups` tags.
+    pub fn new(max_groups: usize) -> ShareReassembler {
+        ShareReassembler {
+            max_groups,
+            groups: VecDeque::new(),
+        }
+    }
+
+    /// Get the number of tags being tracked.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Check whether no tags are being tracked.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Forget all received shares.
+    pub fn clear(&mut self) {
+        self.groups.clear();
+    }
+
+    /// Add a received share, returning its TCN if this share completes it.
+    ///
+    /// Shares that repeat an index, disagree on the threshold with earlier
+    /// shares of their tag, or belong to an already reconstructed TCN are
+    /// ignored.
+    pub fn push(&mut self, share: TcnShare) -> Option<TemporaryContactNumber> {
+        if share.threshold == 0 || share.index == 0 || self.max_groups == 0 {
+            return None;
+        }
+        let group = match self.groups.iter_mut().position(|g| g.tag == share.tag) {
+            Some(position) => &mut self.groups[position],
+            None => {
+                if self.groups.len() == self.max_groups {
+                    self.groups.pop_front();
+                }
+                self.groups.push_back(ShareGroup {
+                    tag: share.tag,
+                    shares: Vec::new(),
+                    complete: false,
+                });
+                self.groups.back_mut().expect("group was just added")
+            }
+        };
+
+        if group.complete
+            || group
+                .shares
+                .iter()
+                .any(|other| other.index == share.index || other.threshold != share.threshold)
+        {
+            return None;
+        }
+        group.shares.push(share);
+        if group.shares.len() < usize::from(share.threshold) {
+            return None;
+        }
+        let tcn = TemporaryContactNumber::reconstruct(&group.shares).ok();
+        group.shares = Vec::new();
+        group.complete = true;
+        tcn
+    }
+}
@@ -0,0 +1,297 @@
+use alloc::{vec, vec::Vec};
+use core::convert::{TryFrom, TryInto};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use hkdf::Hkdf;
+use rand_core::{CryptoRng, RngCore};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use super::{Error, ReportAuthorizationKey, TemporaryContactKey};
+
+const MAGIC: &[u8; 4] = b"TCNK";
+const VERSION: u8 = 1;
+
+const KDF_HKDF_SHA256: u8 = 0;
+const KDF_ARGON2ID: u8 = 1;
+const HKDF_INFO: &[u8] = b"TCN key storage v1";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const ENTRY_LEN: usize = 32 + 2 + 32 + 32;
+
+/// The largest Argon2 memory (in KiB), time and parallelism costs accepted
+/// when opening a container, so that a malicious container cannot exhaust the
+/// device.  This allows up to 256 MiB of memory.
+const MAX_M_COST: u32 = 1 << 18;
+const MAX_T_COST: u32 = 16;
+const MAX_P_COST: u32 = 16;
+
+/// Argon2id cost parameters used to derive a wrapping key from a password.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PasswordParams {
+    /// Memory cost, in KiB.
+    pub m_cost: u32,
+    /// Number of iterations.
+    pub t_cost: u32,
+    /// Degree of parallelism.
+    pub p_cost: u32,
+}
+
+impl Default for PasswordParams {
+    /// The OWASP minimum recommendation: 19 MiB of memory, 2 iterations and 1 lane.
+    fn default() -> PasswordParams {
+        PasswordParams {
+            m_cost: 19 * 1024,
+            t_cost: 2,
+            p_cost: 1,
+        }
+    }
+}
+
+/// A report authorization key together with its current ratchet state.
+#[derive(Clone, Debug)]
+pub struct KeyRingEntry {
+    /// The report authorization key.
+    pub rak: ReportAuthorizationKey,
+    /// The most recent temporary contact key derived from `rak`.
+    pub tck: TemporaryContactKey,
+}
+
+/// The secret key state of a device, which can be sealed into an encrypted
+/// at-rest storage container.
+///
+/// # Container format
+///
+/// A container consists of a header followed by the XChaCha20-Poly1305
+/// encryption of the key ring, with the whole header as associated data so
+/// that any modification is detected when opening it.  The header is
+///
+/// - the magic bytes `TCNK` and a version byte, currently `1`;
+/// - a KDF identifier byte: `0` for a 32-byte wrapping key expanded with
+///   HKDF-SHA256, or `1` for a password stretched with Argon2id, in which
+///   case it is followed by the `m_cost`, `t_cost` and `p_cost` parameters as
+///   little-endian `u32`s, which may not exceed 256 MiB of memory, 16
+///   iterations or 16 lanes;
+/// - a random 16-byte KDF salt and a random 24-byte nonce.
+///
+/// The plaintext is a little-endian `u16` entry count followed by each entry's
+/// serialized report authorization key and temporary contact key.
+#[derive(Clone, Debug, Default)]
+pub struct KeyRing {
+    /// The entries of the key ring.
+    pub entries: Vec<KeyRingEntry>,
+}
+
+/// How the container's encryption key is derived.
+enum Wrapping<'a> {
+    Key(&'a [u8; 32]),
+    Password(&'a [u8], PasswordParams),
+}
+
+impl Wrapping<'_> {
+    fn kdf_id(&self) -> u8 {
+        match self {
+            Wrapping::Key(_) => KDF_HKDF_SHA256,
+            Wrapping::Password(..) => KDF_ARGON2ID,
+        }
+    }
+
+    fn derive_key(&self, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, Error> {
+        let mut key = Zeroizing::new([0; 32]);
+        match self {
+            Wrapping::Key(ikm) => Hkdf::<Sha256>::new(Some(salt), &ikm[..])
+                .expand(HKDF_INFO, &mut key[..])
+                .expect("32 bytes is a valid HKDF-SHA256 output length"),
+            Wrapping::Password(password, params) => {
+                if params.m_cost > MAX_M_COST
+                    || params.t_cost > MAX_T_COST
+                    || params.p_cost > MAX_P_COST
+                {
+                    return Err(Error::InvalidStorageContainer);
+                }
+                let params = Params::new(params.m_cost, params.t_cost, params.p_cost, Some(32))
+                    .map_err(|_| Error::InvalidStorageContainer)?;
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(password, salt, &mut key[..])
+                    .map_err(|_| Error::InvalidStorageContainer)?;
+            }
+        }
+        Ok(key)
+    }
+}
+
+impl KeyRing {
+    /// Seal this key ring into a container encrypted under a 32-byte wrapping
+    /// key, such as one held by a platform keystore.
+    pub fn seal_with_key<R: RngCore + CryptoRng>(
+        &self,
+        key: &[u8; 32],
+        rng: R,
+    ) -> Result<Vec<u8>, Error> {
+        self.seal(Wrapping::Key(key), rng)
+    }
+
+    /// Seal this key ring into a container encrypted under a password.
+    pub fn seal_with_password<R: RngCore + CryptoRng>(
+        &self,
+        password: &[u8],
+        params: PasswordParams,
+        rng: R,
+    ) -> Result<Vec<u8>, Error> {
+        self.seal(Wrapping::Password(password, params), rng)
+    }
+
+    /// Open a container sealed with [`seal_with_key`](KeyRing::seal_with_key).
+    pub fn open_with_key(container: &[u8], key: &[u8; 32]) -> Result<KeyRing, Error> {
+        KeyRing::open(container, |kdf_id, _| match kdf_id {
+            KDF_HKDF_SHA256 => Ok(Wrapping::Key(key)),
+            _ => Err(Error::InvalidStorageContainer),
+        })
+    }
+
+    /// Open a container sealed with
+    /// [`seal_with_password`](KeyRing::seal_with_password).
+    pub fn open_with_password(container: &[u8], password: &[u8]) -> Result<KeyRing, Error> {
+        KeyRing::open(container, |kdf_id, params| match (kdf_id, params) {
+            (KDF_ARGON2ID, Some(params)) => Ok(Wrapping::Password(password, params)),
+            _ => Err(Error::InvalidStorageContainer),
+        })
+    }
+
+    fn seal<R: RngCore + CryptoRng>(
+        &self,
+        wrapping: Wrapping<'_>,
+        mut rng: R,
+    ) -> Result<Vec<u8>, Error> {
+        let mut salt = [0; SALT_LEN];
+        let mut nonce = [0; NONCE_LEN];
+        rng.fill_bytes(&mut salt);
+        rng.fill_bytes(&mut nonce);
+
+        let mut container = Vec::new();
+        container.extend_from_slice(MAGIC);
+        container.push(VERSION);
+        container.push(wrapping.kdf_id());
+        if let Wrapping::Password(_, params) = &wrapping {
+            container.extend_from_slice(&params.m_cost.to_le_bytes());
+            container.extend_from_slice(&params.t_cost.to_le_bytes());
+            container.extend_from_slice(&params.p_cost.to_le_bytes());
+        }
+        container.extend_from_slice(&salt);
+        container.extend_from_slice(&nonce);
+
+        let key = wrapping.derive_key(&salt)?;
+        let plaintext = self.encode_payload()?;
+        let ciphertext = XChaCha20Poly1305::new(key.as_ref().into())
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: &container,
+                },
+            )
+            .map_err(|_| Error::InvalidStorageContainer)?;
+        container.extend_from_slice(&ciphertext);
+        Ok(container)
+    }
+
+    fn open<'a, F>(container: &[u8], wrapping: F) -> Result<KeyRing, Error>
+    where
+        F: FnOnce(u8, Option<PasswordParams>) -> Result<Wrapping<'a>, Error>,
+    {
+        let mut rest = container;
+        let mut take = |len: usize| {
+            if rest.len() < len {
+                return Err(Error::InvalidStorageContainer);
+            }
+            let (head, tail) = rest.split_at(len);
+            rest = tail;
+            Ok(head)
+        };
+        let take_u32 = |bytes: &[u8]| u32::from_le_bytes(bytes.try_into().expect("4 bytes"));
+
+        if take(MAGIC.len())? != MAGIC {
+            return Err(Error::InvalidStorageContainer);
+        }
+        match take(1)?[0] {
+            VERSION => {}
+            version => return Err(Error::UnsupportedStorageVersion(version)),
+        }
+        let kdf_id = take(1)?[0];
+        let params = match kdf_id {
+            KDF_HKDF_SHA256 => None,
+            KDF_ARGON2ID => Some(PasswordParams {
+                m_cost: take_u32(take(4)?),
+                t_cost: take_u32(take(4)?),
+                p_cost: take_u32(take(4)?),
+            }),
+            _ => return Err(Error::InvalidStorageContainer),
+        };
+        let salt = take(SALT_LEN)?;
+        let nonce = take(NONCE_LEN)?;
+        let ciphertext = rest;
+        let header = &container[..container.len() - ciphertext.len()];
+
+        let key = wrapping(kdf_id, params)?.derive_key(salt)?;
+        let plaintext = Zeroizing::new(
+            XChaCha20Poly1305::new(key.as_ref().into())
+                .decrypt(
+                    XNonce::from_slice(nonce),
+                    Payload {
+                        msg: ciphertext,
+                        aad: header,
+                    },
+                )
+                .map_err(|_| Error::StorageDecryptionFailed)?,
+        );
+        KeyRing::decode_payload(&plaintext)
+    }
+
+    fn encode_payload(&self) -> Result<Zeroizing<Vec<u8>>, Error> {
+        let count =
+            u16::try_from(self.entries.len()).map_err(|_| Error::InvalidStorageContainer)?;
+        let mut payload = Zeroizing::new(vec![0; 2 + ENTRY_LEN * self.entries.len()]);
+        payload[..2].copy_from_slice(&count.to_le_bytes());
+        for (entry, out) in self
+            .entries
+            .iter()
+            .zip(payload[2..].chunks_exact_mut(ENTRY_LEN))
+        {
+            entry.rak.encode_to_slice(&mut out[..32])?;
+            entry.tck.encode_to_slice(&mut out[32..])?;
+        }
+        Ok(payload)
+    }
+
+    fn decode_payload(payload: &[u8]) -> Result<KeyRing, Error> {
+        if payload.len() < 2 {
+            return Err(Error::InvalidStorageContainer);
+        }
+        let count = u16::from_le_bytes([payload[0], payload[1]]) as usize;
+        if payload.len() != 2 + ENTRY_LEN * count {
+            return Err(Error::InvalidStorageContainer);
+        }
+
+        let entries = payload[2..]
+            .chunks_exact(ENTRY_LEN)
+            .map(|bytes| {
+                let entry = KeyRingEntry {
+                    rak: ReportAuthorizationKey::decode_from_slice(&bytes[..32])?,
+                    tck: TemporaryContactKey::decode_from_slice(&bytes[32..])?,
+                };
+                // The ratchet state must belong to the report authorization key.
+                if entry.tck.rvk == ed25519_zebra::VerificationKeyBytes::from(&entry.rak.rak) {
+                    Ok(entry)
+                } else {
+                    Err(Error::InvalidStorageContainer)
+                }
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(KeyRing { entries })
+    }
+}
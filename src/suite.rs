@@ -0,0 +1,210 @@
+use core::{convert::TryFrom, fmt};
+
+use sha2::{Digest, Sha256};
+
+use super::{Error, ReportAuthorizationKey};
+
+const H_TCK_DOMAIN_SEP: &[u8; 5] = b"H_TCK";
+const H_TCN_DOMAIN_SEP: &[u8; 5] = b"H_TCN";
+
+/// Identifies a [`CipherSuite`] in a versioned report.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(u8)]
+pub enum CipherSuiteId {
+    /// [`Sha256Ed25519`], the original suite.
+    #[default]
+    Sha256Ed25519 = 0,
+    /// [`Blake3Ed25519`].
+    Blake3Ed25519 = 1,
+}
+
+mod private {
+    /// Prevents implementations of [`CipherSuite`](super::CipherSuite)
+    /// outside this crate.
+    pub trait Sealed {}
+
+    impl Sealed for super::Sha256Ed25519 {}
+    impl Sealed for super::Blake3Ed25519 {}
+}
+
+/// The primitives used to derive temporary contact keys and numbers and to
+/// sign reports.
+///
+/// Every suite uses 32-byte report authorization and verification keys,
+/// 32-byte temporary contact keys and 64-byte signatures, so that only the
+/// suite identifier in a report changes between suites.  The hash functions
+/// must be domain-separated from each other.
+///
+/// The set of suites is closed: this trait is sealed and implemented only by
+/// [`Sha256Ed25519`] and [`Blake3Ed25519`], because reports identify their
+/// suite by a [`CipherSuiteId`], and verifiers select the primitives from that
+/// identifier.  Adding a suite requires a new identifier in this crate.
+pub trait CipherSuite: private::Sealed + Copy + Eq + fmt::Debug {
+    /// The identifier of this suite in versioned reports.
+    const ID: CipherSuiteId;
+
+    /// Compute `tck_0 ← H_tck(rak)`.
+    fn hash_tck_0(rak: &[u8; 32]) -> [u8; 32];
+
+    /// Compute `tck_i ← H_tck(rvk || tck_{i-1})`.
+    fn hash_tck(rvk: &[u8; 32], tck_bytes: &[u8; 32]) -> [u8; 32];
+
+    /// Compute `tcn_i ← H_tcn(le_u16(i) || tck_i)`.
+    fn hash_tcn(index: u16, tck_bytes: &[u8; 32]) -> [u8; 16];
+
+    /// Sign a serialized report with the report authorization key.
+    fn sign(rak: &ReportAuthorizationKey, report: &[u8]) -> [u8; 64];
+
+    /// Verify the signature of a serialized report against the report
+    /// verification key.
+    fn verify(rvk: &[u8; 32], sig: &[u8; 64], report: &[u8]) -> Result<(), Error>;
+}
+
+fn ed25519_sign(rak: &ReportAuthorizationKey, report: &[u8]) -> [u8; 64] {
+    rak.rak.sign(report).to_bytes()
+}
+
+fn ed25519_verify(rvk: &[u8; 32], sig: &[u8; 64], report: &[u8]) -> Result<(), Error> {
+    ed25519_zebra::VerificationKey::try_from(*rvk)
+        .and_then(|vk| vk.verify(&ed25519_zebra::Signature::from_bytes(sig), report))
+        .map_err(|_| Error::ReportVerificationFailed)
+}
+
+/// The original suite: SHA-256 with domain separators for `H_tck` and
+/// `H_tcn`, and Ed25519 signatures.
+///
+/// This is the default suite, and the only one that version 0 and 1 reports
+/// can use.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Sha256Ed25519;
+
+impl CipherSuite for Sha256Ed25519 {
+    const ID: CipherSuiteId = CipherSuiteId::Sha256Ed25519;
+
+    fn hash_tck_0(rak: &[u8; 32]) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(H_TCK_DOMAIN_SEP)
+            .chain_update(rak)
+            .finalize()
+            .into()
+    }
+
+    fn hash_tck(rvk: &[u8; 32], tck_bytes: &[u8; 32]) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(H_TCK_DOMAIN_SEP)
+            .chain_update(rvk)
+            .chain_update(tck_bytes)
+            .finalize()
+            .into()
+    }
+
+    fn hash_tcn(index: u16, tck_bytes: &[u8; 32]) -> [u8; 16] {
+        let mut tcn = [0; 16];
+        tcn.copy_from_slice(
+            &Sha256::new()
+                .chain_update(H_TCN_DOMAIN_SEP)
+                .chain_update(index.to_le_bytes())
+                .chain_update(tck_bytes)
+                .finalize()[..16],
+        );
+        tcn
+    }
+
+    fn sign(rak: &ReportAuthorizationKey, report: &[u8]) -> [u8; 64] {
+        ed25519_sign(rak, report)
+    }
+
+    fn verify(rvk: &[u8; 32], sig: &[u8; 64], report: &[u8]) -> Result<(), Error> {
+        ed25519_verify(rvk, sig, report)
+    }
+}
+
+/// BLAKE3 with the same domain separators for `H_tck` and `H_tcn`, and
+/// Ed25519 signatures.
+///
+/// Hashing dominates the cost of deriving TCNs and of expanding downloaded
+/// reports.  BLAKE3 is faster than SHA-256 on CPUs without SHA-256
+/// instructions, as on many low-end phones; on CPUs with them, the default
+/// suite may be as fast.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Blake3Ed25519;
+
+impl CipherSuite for Blake3Ed25519 {
+    const ID: CipherSuiteId = CipherSuiteId::Blake3Ed25519;
+
+    fn hash_tck_0(rak: &[u8; 32]) -> [u8; 32] {
+        blake3::Hasher::new()
+            .update(H_TCK_DOMAIN_SEP)
+            .update(rak)
+            .finalize()
+            .into()
+    }
+
+    fn hash_tck(rvk: &[u8; 32], tck_bytes: &[u8; 32]) -> [u8; 32] {
+        blake3::Hasher::new()
+            .update(H_TCK_DOMAIN_SEP)
+            .update(rvk)
+            .update(tck_bytes)
+            .finalize()
+            .into()
+    }
+
+    fn hash_tcn(index: u16, tck_bytes: &[u8; 32]) -> [u8; 16] {
+        let mut tcn = [0; 16];
+        tcn.copy_from_slice(
+            &blake3::Hasher::new()
+                .update(H_TCN_DOMAIN_SEP)
+                .update(&index.to_le_bytes())
+                .update(tck_bytes)
+                .finalize()
+                .as_bytes()[..16],
+        );
+        tcn
+    }
+
+    fn sign(rak: &ReportAuthorizationKey, report: &[u8]) -> [u8; 64] {
+        ed25519_sign(rak, report)
+    }
+
+    fn verify(rvk: &[u8; 32], sig: &[u8; 64], report: &[u8]) -> Result<(), Error> {
+        ed25519_verify(rvk, sig, report)
+    }
+}
+
+/// Dispatch to the suite identified in a report.
+impl CipherSuiteId {
+    pub(crate) fn hash_tck_0(self, rak: &[u8; 32]) -> [u8; 32] {
+        match self {
+            CipherSuiteId::Sha256Ed25519 => Sha256Ed25519::hash_tck_0(rak),
+            CipherSuiteId::Blake3Ed25519 => Blake3Ed25519::hash_tck_0(rak),
+        }
+    }
+
+    pub(crate) fn hash_tck(self, rvk: &[u8; 32], tck_bytes: &[u8; 32]) -> [u8; 32] {
+        match self {
+            CipherSuiteId::Sha256Ed25519 => Sha256Ed25519::hash_tck(rvk, tck_bytes),
+            CipherSuiteId::Blake3Ed25519 => Blake3Ed25519::hash_tck(rvk, tck_bytes),
+        }
+    }
+
+    pub(crate) fn hash_tcn(self, index: u16, tck_bytes: &[u8; 32]) -> [u8; 16] {
+        match self {
+            CipherSuiteId::Sha256Ed25519 => Sha256Ed25519::hash_tcn(index, tck_bytes),
+            CipherSuiteId::Blake3Ed25519 => Blake3Ed25519::hash_tcn(index, tck_bytes),
+        }
+    }
+
+    pub(crate) fn sign(self, rak: &ReportAuthorizationKey, report: &[u8]) -> [u8; 64] {
+        match self {
+            CipherSuiteId::Sha256Ed25519 => Sha256Ed25519::sign(rak, report),
+            CipherSuiteId::Blake3Ed25519 => Blake3Ed25519::sign(rak, report),
+        }
+    }
+
+    pub(crate) fn verify(self, rvk: &[u8; 32], sig: &[u8; 64], report: &[u8]) -> Result<(), Error> {
+        match self {
+            CipherSuiteId::Sha256Ed25519 => Sha256Ed25519::verify(rvk, sig, report),
+            CipherSuiteId::Blake3Ed25519 => Blake3Ed25519::verify(rvk, sig, report),
+        }
+    }
+}
@@ -0,0 +1,386 @@
+//! The `tcn` command-line tool, for inspecting keys, reports and test vectors
+//! without writing Rust.
+//!
+//! Report authorization keys are stored as hex text.  Report files may hold
+//! one or more signed reports, either serialized back-to-back in binary (as
+//! served to clients) or as text with one hex or base64 report per line.
+//! Observation files hold one hex TCN per line.  In text files, blank lines and
+//! lines starting with `#` are ignored.
+
+use std::{
+    collections::BTreeSet,
+    convert::TryInto,
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use tcn::{
+    Blake3Ed25519, CipherSuite, MemoType, ReportAuthorizationKey, ReportVersion, Sha256Ed25519,
+    SignedReport, SignedReportRef, TemporaryContactKey, TemporaryContactNumber,
+};
+
+mod vectors;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(
+    name = "tcn",
+    version,
+    about = "Inspect TCN keys, reports and test vectors"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a new report authorization key, written as secret hex.
+    GenerateRak {
+        /// Write the key to this file instead of standard output.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+    /// Print the temporary contact numbers derived from a key.
+    Tcns {
+        /// The report authorization key file.
+        #[arg(long)]
+        rak: PathBuf,
+        /// The index of the first TCN to print.
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+        from: u16,
+        /// The index of the last TCN to print.
+        #[arg(long)]
+        to: u16,
+        /// The cipher suite used to derive the TCNs.
+        #[arg(long, value_enum, default_value_t = Suite::Sha256)]
+        suite: Suite,
+    },
+    /// Create a signed report disclosing a range of TCNs.
+    CreateReport {
+        /// The report authorization key file.
+        #[arg(long)]
+        rak: PathBuf,
+        /// The memo type: `coepi`, `covidwatch`, or a number.
+        #[arg(long, default_value = "coepi", value_parser = parse_memo_type)]
+        memo_type: MemoType,
+        /// The memo data, as text.
+        #[arg(long, conflicts_with = "memo_file")]
+        memo: Option<String>,
+        /// A file containing the memo data.
+        #[arg(long)]
+        memo_file: Option<PathBuf>,
+        /// The index of the first TCN to disclose.
+        #[arg(long)]
+        from: u16,
+        /// The index of the last TCN to disclose.
+        #[arg(long)]
+        to: u16,
+        /// The cipher suite used to derive the TCNs.
+        #[arg(long, value_enum, default_value_t = Suite::Sha256)]
+        suite: Suite,
+        /// The report wire format version, by default 0, which can be parsed
+        /// by every client, or 2 for suites other than `sha256`.  Version 3
+        /// carries memos longer than 255 bytes.
+        #[arg(long, value_parser = parse_report_version)]
+        report_version: Option<ReportVersion>,
+        /// The output encoding.
+        #[arg(long, value_enum, default_value_t = Encoding::Base64)]
+        encoding: Encoding,
+        /// Write the report to this file instead of standard output.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+    /// Verify the reports in a file and pretty-print their contents.
+    Verify {
+        /// The report file.
+        reports: PathBuf,
+    },
+    /// Verify the reports in a file and print the TCNs they disclose.
+    Expand {
+        /// The report file.
+        reports: PathBuf,
+    },
+    /// Match the reports in a file against a file of observed TCNs.
+    Match {
+        /// The report file.
+        reports: PathBuf,
+        /// The observation file.
+        #[arg(long)]
+        observations: PathBuf,
+    },
+    /// Print the protocol test vectors.
+    Vectors {
+        /// Print the machine-readable conformance suite committed as
+        /// `tests/vectors.json` instead.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Copy, Clone, PartialEq, ValueEnum)]
+enum Suite {
+    Sha256,
+    Blake3,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum Encoding {
+    Base64,
+    Hex,
+    Binary,
+}
+
+fn parse_memo_type(s: &str) -> std::result::Result<MemoType, String> {
+    match s {
+        "coepi" => Ok(MemoType::CoEpiV1),
+        "covidwatch" => Ok(MemoType::CovidWatchV1),
+        s => s
+            .parse::<u8>()
+            .map_err(|_| format!("unknown memo type `{}`", s))?
+            .try_into()
+            .map_err(|e: tcn::Error| e.to_string()),
+    }
+}
+
+fn parse_report_version(s: &str) -> std::result::Result<ReportVersion, String> {
+    match s {
+        "0" => Ok(ReportVersion::V0),
+        "1" => Ok(ReportVersion::V1),
+        "2" => Ok(ReportVersion::V2),
+        "3" => Ok(ReportVersion::V3),
+        s => Err(format!("unsupported report version `{}`", s)),
+    }
+}
+
+fn print_tcns<W: Write, S: CipherSuite>(
+    out: &mut W,
+    tck: TemporaryContactKey<S>,
+    from: u16,
+    to: u16,
+) -> Result<()> {
+    let mut tck = Some(tck);
+    while let Some(key) = tck.take() {
+        if key.index() > to {
+            break;
+        }
+        if key.index() >= from {
+            let tcn = key.temporary_contact_number();
+            writeln!(out, "{} {}", key.index(), tcn)?;
+        }
+        tck = key.ratchet();
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    match cli.command {
+        Command::GenerateRak { out: path } => {
+            let rak = ReportAuthorizationKey::new(rand::thread_rng());
+            let hex = format!("{}\n", rak.expose_secret_hex());
+            match path {
+                Some(path) => fs::write(path, hex)?,
+                None => out.write_all(hex.as_bytes())?,
+            }
+        }
+        Command::Tcns {
+            rak,
+            from,
+            to,
+            suite,
+        } => {
+            let rak = read_rak(&rak)?;
+            match suite {
+                Suite::Sha256 => {
+                    let tck = rak.initial_temporary_contact_key_for::<Sha256Ed25519>();
+                    print_tcns(&mut out, tck, from, to)?
+                }
+                Suite::Blake3 => {
+                    let tck = rak.initial_temporary_contact_key_for::<Blake3Ed25519>();
+                    print_tcns(&mut out, tck, from, to)?
+                }
+            }
+        }
+        Command::CreateReport {
+            rak,
+            memo_type,
+            memo,
+            memo_file,
+            from,
+            to,
+            suite,
+            report_version,
+            encoding,
+            out: path,
+        } => {
+            let memo_data = match (memo, memo_file) {
+                (_, Some(path)) => fs::read(path)?,
+                (Some(memo), None) => memo.into_bytes(),
+                (None, None) => Vec::new(),
+            };
+            let rak = read_rak(&rak)?;
+            let signed_report = match (suite, report_version) {
+                (Suite::Sha256, version) => rak.create_versioned_report(
+                    version.unwrap_or(ReportVersion::V0),
+                    memo_type,
+                    memo_data,
+                    from,
+                    to,
+                )?,
+                (Suite::Blake3, None) => {
+                    rak.create_suite_report::<Blake3Ed25519>(memo_type, memo_data, from, to)?
+                }
+                (Suite::Blake3, Some(ReportVersion::V0))
+                | (Suite::Blake3, Some(ReportVersion::V1)) => {
+                    return Err("only report versions 2 and 3 identify the cipher suite".into())
+                }
+                (Suite::Blake3, Some(version)) => rak
+                    .create_versioned_suite_report::<Blake3Ed25519>(
+                        version, memo_type, memo_data, from, to,
+                    )?,
+            };
+            let bytes = match encoding {
+                Encoding::Base64 => format!("{}\n", signed_report.to_base64()?).into_bytes(),
+                Encoding::Hex => format!("{}\n", signed_report.to_hex()?).into_bytes(),
+                Encoding::Binary => {
+                    let mut bytes = Vec::new();
+                    signed_report.write(&mut bytes)?;
+                    bytes
+                }
+            };
+            match path {
+                Some(path) => fs::write(path, bytes)?,
+                None => out.write_all(&bytes)?,
+            }
+        }
+        Command::Verify { reports } => {
+            let mut failures = 0;
+            for (i, signed_report) in read_signed_reports(&reports)?.into_iter().enumerate() {
+                match signed_report.verify() {
+                    Ok(report) => {
+                        let range = report.index_range();
+                        writeln!(out, "report {}: verified", i + 1)?;
+                        writeln!(out, "  version:   {}", report.version() as u8)?;
+                        writeln!(out, "  suite:     {:?}", report.suite())?;
+                        writeln!(
+                            out,
+                            "  rvk:       {}",
+                            hex::encode(report.verification_key_bytes())
+                        )?;
+                        writeln!(
+                            out,
+                            "  indices:   {}..={} ({} TCNs)",
+                            range.start(),
+                            range.end(),
+                            range.clone().count()
+                        )?;
+                        writeln!(out, "  memo type: {:?}", report.memo_type())?;
+                        match std::str::from_utf8(report.memo_data()) {
+                            Ok(text) => writeln!(out, "  memo:      {:?}", text)?,
+                            Err(_) => {
+                                writeln!(out, "  memo:      0x{}", hex::encode(report.memo_data()))?
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        writeln!(out, "report {}: {}", i + 1, e)?;
+                        failures += 1;
+                    }
+                }
+            }
+            if failures > 0 {
+                return Err(format!("{} report(s) failed verification", failures).into());
+            }
+        }
+        Command::Expand { reports } => {
+            for signed_report in read_signed_reports(&reports)? {
+                for tcn in signed_report.verify()?.temporary_contact_numbers() {
+                    writeln!(out, "{}", tcn)?;
+                }
+            }
+        }
+        Command::Match {
+            reports,
+            observations,
+        } => {
+            let observed = read_observations(&observations)?;
+            let mut matches = 0;
+            for (i, signed_report) in read_signed_reports(&reports)?.into_iter().enumerate() {
+                let report = signed_report.verify()?;
+                for (j, tcn) in report.index_range().zip(report.temporary_contact_numbers()) {
+                    if observed.contains(&tcn) {
+                        writeln!(out, "report {}: tcn_{} {}", i + 1, j, tcn)?;
+                        matches += 1;
+                    }
+                }
+            }
+            writeln!(out, "{} match(es)", matches)?;
+        }
+        Command::Vectors { json } => {
+            if json {
+                writeln!(out, "{}", serde_json::to_string_pretty(&vectors::json()?)?)?;
+            } else {
+                vectors::text(&mut out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_rak(path: &Path) -> Result<ReportAuthorizationKey> {
+    let text = fs::read_to_string(path)?;
+    Ok(ReportAuthorizationKey::from_secret_hex(text.trim())?)
+}
+
+/// Iterate over the non-empty, non-comment lines of a text file.
+fn lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn read_signed_reports(path: &Path) -> Result<Vec<SignedReport>> {
+    let bytes = fs::read(path)?;
+    // Text can never parse as a binary report, whose memo type byte is not
+    // printable, so try the binary format first.
+    if let Ok(reports) = SignedReportRef::parse_all(&bytes)
+        .map(|r| r.map(|r| r.to_signed_report()))
+        .collect::<std::result::Result<Vec<_>, _>>()
+    {
+        return Ok(reports);
+    }
+    let text = std::str::from_utf8(&bytes).map_err(|_| "malformed binary report file")?;
+    lines(text)
+        .map(|line| {
+            let report = if line.bytes().all(|b| b.is_ascii_hexdigit()) {
+                SignedReport::from_hex(line)?
+            } else {
+                SignedReport::from_base64(line)?
+            };
+            Ok(report)
+        })
+        .collect()
+}
+
+/// Read the observed TCNs into a set, so that matching each disclosed TCN
+/// takes logarithmic rather than linear time.
+fn read_observations(path: &Path) -> Result<BTreeSet<TemporaryContactNumber>> {
+    let text = fs::read_to_string(path)?;
+    Ok(lines(&text)
+        .map(str::parse)
+        .collect::<std::result::Result<_, _>>()?)
+}
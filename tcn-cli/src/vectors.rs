@@ -0,0 +1,242 @@
+//! Generation of the protocol test vectors.
+
+use std::io::Write;
+
+use rand_chacha::{rand_core::SeedableRng, ChaChaRng};
+use serde_json::{json, Value};
+use tcn::{
+    Blake3Ed25519, CipherSuiteId, MemoType, ReportAuthorizationKey, ReportVersion,
+    TemporaryContactKey,
+};
+
+use super::Result;
+
+/// The seed of the (insecure!) RNG used to generate the vector key.
+const SEED: [u8; 32] = [0x19; 32];
+
+/// Indices, beyond the first few, at which TCNs are included in the vectors.
+const SPOT_INDICES: [u16; 4] = [100, 1000, 10000, u16::MAX];
+
+/// Generate the report authorization key used for all vectors.
+fn rak() -> ReportAuthorizationKey {
+    // This is insecure!! It's only done for reproducibility.
+    ReportAuthorizationKey::new(ChaChaRng::from_seed(SEED))
+}
+
+fn tck_bytes(tck: &TemporaryContactKey) -> Result<[u8; 66]> {
+    let mut bytes = [0; 66];
+    tck.encode_to_slice(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Print the same test vectors as `cargo test generate_test_vectors`.
+pub fn text<W: Write>(out: &mut W) -> Result<()> {
+    let rak = rak();
+
+    let mut buf = Vec::new();
+    rak.write(&mut buf)?;
+    writeln!(out, "rak:\n{}", hex::encode(&buf))?;
+
+    let mut tck = rak.initial_temporary_contact_key();
+    writeln!(out, "rvk:\n{}", hex::encode(&tck_bytes(&tck)?[2..34]))?;
+
+    for i in 1..10 {
+        writeln!(out, "tck_{}:\n{}", i, hex::encode(&tck_bytes(&tck)?[34..]))?;
+        writeln!(out, "tcn_{}:\n{}", i, tck.temporary_contact_number())?;
+        tck = tck.ratchet().expect("index is below u16::MAX");
+    }
+
+    let signed_report = rak.create_report(MemoType::CoEpiV1, b"symptom data".to_vec(), 2, 10)?;
+    writeln!(out, "signed_report:\n{}", signed_report.to_hex()?)?;
+    Ok(())
+}
+
+/// The version, suite, memo type, memo data, `j_1` and `j_2` of a report
+/// vector.
+type ReportCase = (
+    ReportVersion,
+    CipherSuiteId,
+    MemoType,
+    &'static [u8],
+    u16,
+    u16,
+);
+
+/// Sign arbitrary report bytes with the vector key, so that negative vectors
+/// are rejected for their contents rather than their signature.
+fn sign(rak: &ReportAuthorizationKey, report: &[u8]) -> Result<Vec<u8>> {
+    let mut secret = [0; 32];
+    rak.encode_to_slice(&mut secret)?;
+    let sig = ed25519_zebra::SigningKey::from(secret).sign(report);
+    Ok([report, &sig.to_bytes()[..]].concat())
+}
+
+/// Generate the machine-readable conformance suite, `tests/vectors.json`.
+pub fn json() -> Result<Value> {
+    let rak = rak();
+    let mut rak_bytes = [0; 32];
+    rak.encode_to_slice(&mut rak_bytes)?;
+
+    let mut tck = rak.initial_temporary_contact_key();
+    let rvk = hex::encode(&tck_bytes(&tck)?[2..34]);
+    let mut tck_chain = Vec::new();
+    let mut spot_tcns = Vec::new();
+    loop {
+        let bytes = tck_bytes(&tck)?;
+        if tck.index() < 10 {
+            tck_chain.push(json!({
+                "index": tck.index(),
+                "tck": hex::encode(&bytes[34..]),
+                "tcn": tck.temporary_contact_number().to_hex(),
+            }));
+        }
+        if SPOT_INDICES.contains(&tck.index()) {
+            spot_tcns.push(json!({
+                "index": tck.index(),
+                "tcn": tck.temporary_contact_number().to_hex(),
+            }));
+        }
+        match tck.ratchet() {
+            Some(next) => tck = next,
+            None => break,
+        }
+    }
+
+    use CipherSuiteId::{Blake3Ed25519 as Blake3, Sha256Ed25519 as Sha256};
+    use MemoType::{CoEpiV1, CovidWatchV1};
+    use ReportVersion::{V0, V1, V2, V3};
+    let cases: [ReportCase; 12] = [
+        (V0, Sha256, CoEpiV1, b"symptom data", 2, 10),
+        (V0, Sha256, CovidWatchV1, b"test result", 1, 1),
+        (V0, Sha256, CovidWatchV1, b"", 20, 10),
+        (V0, Sha256, CoEpiV1, &[0xab; 255], 65530, u16::MAX),
+        (V1, Sha256, CoEpiV1, b"symptom data", 2, 10),
+        (V1, Sha256, CovidWatchV1, &[0xcd; 255], 1, 1),
+        (V2, Sha256, CoEpiV1, b"symptom data", 2, 10),
+        (V2, Blake3, CoEpiV1, b"symptom data", 2, 10),
+        (V2, Blake3, CovidWatchV1, b"", 65530, u16::MAX),
+        (V3, Sha256, CoEpiV1, b"symptom data", 2, 10),
+        (V3, Sha256, CovidWatchV1, &[0xef; 1000], 1, 1),
+        (V3, Blake3, CovidWatchV1, &[0xef; 256], 1, 1),
+    ];
+    let mut reports = Vec::new();
+    for &(version, suite, memo_type, memo_data, j_1, j_2) in cases.iter() {
+        let memo = memo_data.to_vec();
+        let signed_report = match suite {
+            Sha256 => rak.create_versioned_report(version, memo_type, memo, j_1, j_2)?,
+            Blake3 => rak.create_versioned_suite_report::<Blake3Ed25519>(
+                version, memo_type, memo, j_1, j_2,
+            )?,
+        };
+        let mut bytes = Vec::new();
+        signed_report.write(&mut bytes)?;
+        let (report, sig) = bytes.split_at(bytes.len() - 64);
+        let tcns = signed_report
+            .verify()?
+            .temporary_contact_numbers()
+            .map(|tcn| tcn.to_hex())
+            .collect::<Vec<_>>();
+        reports.push(json!({
+            "version": version as u8,
+            "suite": suite as u8,
+            "memo_type": memo_type as u8,
+            "memo_data": hex::encode(memo_data),
+            "j_1": j_1,
+            "j_2": j_2,
+            "report": hex::encode(report),
+            "signature": hex::encode(sig),
+            "signed_report": hex::encode(&bytes),
+            "tcns": tcns,
+        }));
+    }
+
+    // Build the invalid reports from a valid one.
+    let mut valid = Vec::new();
+    rak.create_report(MemoType::CoEpiV1, b"symptom data".to_vec(), 2, 10)?
+        .write(&mut valid)?;
+    let report = &valid[..valid.len() - 64];
+
+    let mut bad_signature = valid.clone();
+    *bad_signature.last_mut().unwrap() ^= 1;
+    let mut zero_index = report.to_vec();
+    zero_index[64..66].copy_from_slice(&0u16.to_le_bytes());
+    let truncated_memo = report[..70 + 5].to_vec();
+    let mut unknown_type = report.to_vec();
+    unknown_type[68] = 2;
+    // The reserved memo type inside a version 1 envelope.
+    let reserved_type = [
+        &report[..68],
+        &[MemoType::Reserved as u8, 1, MemoType::Reserved as u8],
+        &report[69..],
+    ]
+    .concat();
+    // Version 4 and unassigned cipher suite envelopes around the same memo.
+    let unknown_version = [&report[..68], &[MemoType::Reserved as u8, 4], &report[68..]].concat();
+    let unknown_suite = [
+        &report[..68],
+        &[MemoType::Reserved as u8, 2, 2],
+        &report[68..],
+    ]
+    .concat();
+    // A version 3 report claiming a 300-byte memo, of which only 12 follow.
+    let short_extended_memo = [
+        &report[..68],
+        &[MemoType::Reserved as u8, 3, 0, MemoType::CoEpiV1 as u8],
+        &300u16.to_le_bytes(),
+        &report[70..],
+    ]
+    .concat();
+
+    let invalid = vec![
+        json!({
+            "description": "the last bit of the signature is flipped",
+            "signed_report": hex::encode(&bad_signature),
+            "error": "ReportVerificationFailed",
+        }),
+        json!({
+            "description": "j_1 = 0, validly signed",
+            "signed_report": hex::encode(sign(&rak, &zero_index)?),
+            "error": "InvalidReportIndex",
+        }),
+        json!({
+            "description": "the memo is truncated to 5 of its 12 bytes, with no signature",
+            "signed_report": hex::encode(&truncated_memo),
+            "error": "InvalidLength",
+        }),
+        json!({
+            "description": "unassigned memo type 2, validly signed",
+            "signed_report": hex::encode(sign(&rak, &unknown_type)?),
+            "error": "UnknownMemoType",
+        }),
+        json!({
+            "description": "reserved memo type 0xff in a version 1 report, validly signed",
+            "signed_report": hex::encode(sign(&rak, &reserved_type)?),
+            "error": "UnknownMemoType",
+        }),
+        json!({
+            "description": "unassigned report version 4, validly signed",
+            "signed_report": hex::encode(sign(&rak, &unknown_version)?),
+            "error": "UnsupportedReportVersion",
+        }),
+        json!({
+            "description": "unassigned cipher suite 2 in a version 2 report, validly signed",
+            "signed_report": hex::encode(sign(&rak, &unknown_suite)?),
+            "error": "UnsupportedCipherSuite",
+        }),
+        json!({
+            "description": "a version 3 memo is truncated to 12 of its 300 bytes, with no signature",
+            "signed_report": hex::encode(&short_extended_memo),
+            "error": "InvalidLength",
+        }),
+    ];
+
+    Ok(json!({
+        "description": "TCN protocol conformance vectors. Regenerate with `cargo run -p tcn-cli -- vectors --json > tests/vectors.json`.",
+        "rak": hex::encode(rak_bytes),
+        "rvk": rvk,
+        "tck_chain": tck_chain,
+        "tcns": spot_tcns,
+        "reports": reports,
+        "invalid_reports": invalid,
+    }))
+}
@@ -0,0 +1,239 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+fn tcn(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tcn"))
+        .args(args)
+        .output()
+        .expect("the tcn binary should run")
+}
+
+fn stdout(args: &[&str]) -> String {
+    let output = tcn(args);
+    assert!(
+        output.status.success(),
+        "tcn {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tcn-cli-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn path(dir: &Path, name: &str) -> String {
+    dir.join(name).to_str().unwrap().to_string()
+}
+
+#[test]
+fn keys_reports_and_matching() {
+    let dir = scratch_dir("reports");
+    let rak = path(&dir, "rak.hex");
+    stdout(&["generate-rak", "--out", &rak]);
+
+    let tcns = stdout(&["tcns", "--rak", &rak, "--from", "1", "--to", "20"])
+        .lines()
+        .map(|line| line.split(' ').nth(1).unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(tcns.len(), 20);
+
+    // The same report in every supported encoding and report version.
+    for (encoding, version) in &[("base64", "0"), ("hex", "1"), ("binary", "1")] {
+        let report = path(&dir, &format!("report.{}", encoding));
+        stdout(&[
+            "create-report",
+            "--rak",
+            &rak,
+            "--memo-type",
+            "covidwatch",
+            "--memo",
+            "positive test",
+            "--from",
+            "5",
+            "--to",
+            "15",
+            "--report-version",
+            version,
+            "--encoding",
+            encoding,
+            "--out",
+            &report,
+        ]);
+
+        let verified = stdout(&["verify", &report]);
+        assert!(verified.contains("report 1: verified"));
+        assert!(verified.contains(&format!("version:   {}", version)));
+        assert!(verified.contains("indices:   5..=15 (11 TCNs)"));
+        assert!(verified.contains("memo type: CovidWatchV1"));
+        assert!(verified.contains("\"positive test\""));
+
+        let expanded = stdout(&["expand", &report]);
+        assert_eq!(expanded.lines().collect::<Vec<_>>(), tcns[4..15].to_vec());
+    }
+
+    let observations = path(&dir, "observations.txt");
+    fs::write(
+        &observations,
+        format!(
+            "# seen today\n{}\n\n{}\n{}\n",
+            tcns[0],
+            tcns[9],
+            "00".repeat(16)
+        ),
+    )
+    .unwrap();
+    let matched = stdout(&[
+        "match",
+        &path(&dir, "report.base64"),
+        "--observations",
+        &observations,
+    ]);
+    assert_eq!(
+        matched,
+        format!("report 1: tcn_10 {}\n1 match(es)\n", tcns[9])
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn cipher_suites() {
+    let dir = scratch_dir("suites");
+    let rak = path(&dir, "rak.hex");
+    stdout(&["generate-rak", "--out", &rak]);
+    let tcns = |suite| {
+        stdout(&["tcns", "--rak", &rak, "--to", "10", "--suite", suite])
+            .lines()
+            .map(|line| line.split(' ').nth(1).unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    let blake3_tcns = tcns("blake3");
+    assert_ne!(blake3_tcns, tcns("sha256"));
+
+    let report = path(&dir, "report.hex");
+    stdout(&[
+        "create-report",
+        "--rak",
+        &rak,
+        "--from",
+        "2",
+        "--to",
+        "10",
+        "--suite",
+        "blake3",
+        "--encoding",
+        "hex",
+        "--out",
+        &report,
+    ]);
+    let verified = stdout(&["verify", &report]);
+    assert!(verified.contains("version:   2"));
+    assert!(verified.contains("suite:     Blake3Ed25519"));
+    let expanded = stdout(&["expand", &report]);
+    assert_eq!(
+        expanded.lines().collect::<Vec<_>>(),
+        blake3_tcns[1..].to_vec()
+    );
+
+    // Version 3 reports identify the suite too.
+    let v3_report = path(&dir, "report-v3.hex");
+    stdout(&[
+        "create-report",
+        "--rak",
+        &rak,
+        "--from",
+        "2",
+        "--to",
+        "10",
+        "--suite",
+        "blake3",
+        "--report-version",
+        "3",
+        "--encoding",
+        "hex",
+        "--out",
+        &v3_report,
+    ]);
+    let verified = stdout(&["verify", &v3_report]);
+    assert!(verified.contains("version:   3"));
+    assert!(verified.contains("suite:     Blake3Ed25519"));
+    assert_eq!(stdout(&["expand", &v3_report]), expanded);
+
+    // Older report versions cannot identify the suite.
+    let output = tcn(&[
+        "create-report",
+        "--rak",
+        &rak,
+        "--from",
+        "1",
+        "--to",
+        "2",
+        "--suite",
+        "blake3",
+        "--report-version",
+        "1",
+    ]);
+    assert!(!output.status.success());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn rejects_tampered_reports() {
+    let dir = scratch_dir("tampered");
+    let rak = path(&dir, "rak.hex");
+    stdout(&["generate-rak", "--out", &rak]);
+    let report = path(&dir, "report.bin");
+    stdout(&[
+        "create-report",
+        "--rak",
+        &rak,
+        "--from",
+        "1",
+        "--to",
+        "2",
+        "--encoding",
+        "binary",
+        "--out",
+        &report,
+    ]);
+    let mut bytes = fs::read(&report).unwrap();
+    bytes[70] ^= 1;
+    fs::write(&report, bytes).unwrap();
+
+    let output = tcn(&["verify", &report]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("report 1: Report verification failed")
+    );
+    assert!(!tcn(&["expand", &report]).status.success());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn prints_test_vectors() {
+    let vectors = stdout(&["vectors"]);
+    assert!(vectors
+        .starts_with("rak:\n577cfdae21fee71579211ab02c418ee0948bacab613cf69d0a4a5ae5a1557dbb\n"));
+    assert!(vectors.contains("tcn_9:\n"));
+    assert!(vectors.contains("signed_report:\n"));
+}
+
+#[test]
+fn exports_the_committed_conformance_vectors() {
+    let committed = include_str!("../../tests/vectors.json");
+    assert_eq!(
+        stdout(&["vectors", "--json"]),
+        committed,
+        "tests/vectors.json is out of date; regenerate it with \
+         cargo run -p tcn-cli -- vectors --json > tests/vectors.json"
+    );
+}
@@ -0,0 +1,303 @@
+/* Generated by cbindgen from tcn-ffi; do not edit by hand. */
+
+#ifndef TCN_H
+#define TCN_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The length of a serialized report authorization key.
+#define TCN_RAK_LEN 32
+
+// The length of a serialized temporary contact key.
+#define TCN_TCK_LEN 66
+
+// The length of a temporary contact number.
+#define TCN_TCN_LEN 16
+
+// The largest possible length of a serialized signed report.
+#define TCN_SIGNED_REPORT_MAX_LEN ((74 + 65535) + 64)
+
+// Status codes returned by the C API.
+//
+// Codes other than `TCN_STATUS_OK` below 100 correspond to the variants of
+// `tcn::Error`; codes from 100 are specific to the C API.
+typedef enum TcnStatus {
+  // The call succeeded.
+  TCN_STATUS_OK = 0,
+  // An unknown memo type was encountered while parsing a report.
+  TCN_STATUS_UNKNOWN_MEMO_TYPE = 1,
+  // Reports cannot include the TCN with index 0.
+  TCN_STATUS_INVALID_REPORT_INDEX = 2,
+  // An underlying I/O error occurred while parsing data.
+  TCN_STATUS_IO = 3,
+  // An oversized memo field was supplied when creating a report.
+  TCN_STATUS_OVERSIZE_MEMO = 4,
+  // A report failed the source integrity check.
+  TCN_STATUS_REPORT_VERIFICATION_FAILED = 5,
+  // A hex string was invalid.
+  TCN_STATUS_INVALID_HEX = 6,
+  // A base64 string was invalid.
+  TCN_STATUS_INVALID_BASE64 = 7,
+  // Decoded data had the wrong length for the value being parsed.
+  TCN_STATUS_INVALID_LENGTH = 8,
+  // An output buffer was too small for the value being encoded.
+  TCN_STATUS_BUFFER_TOO_SMALL = 9,
+  // A key storage container had an unsupported format version.
+  TCN_STATUS_UNSUPPORTED_STORAGE_VERSION = 10,
+  // A key storage container was malformed.
+  TCN_STATUS_INVALID_STORAGE_CONTAINER = 11,
+  // A key storage container could not be decrypted.
+  TCN_STATUS_STORAGE_DECRYPTION_FAILED = 12,
+  // A report's index range was empty, with `j_2 < j_1`.
+  TCN_STATUS_REPORT_RANGE_INVERTED = 13,
+  // A report disclosed more TCNs than allowed by a report policy.
+  TCN_STATUS_REPORT_RANGE_TOO_LARGE = 14,
+  // A report's memo type was not allowed by a report policy.
+  TCN_STATUS_MEMO_TYPE_NOT_ALLOWED = 15,
+  // A report's memo was longer than allowed by a report policy.
+  TCN_STATUS_MEMO_TOO_LONG = 16,
+  // A report's memo did not satisfy the schema of its memo type.
+  TCN_STATUS_INVALID_MEMO = 17,
+  // A report bundle failed the publisher signature check.
+  TCN_STATUS_BUNDLE_VERIFICATION_FAILED = 18,
+  // A report used a wire format version that is not supported.
+  TCN_STATUS_UNSUPPORTED_REPORT_VERSION = 19,
+  // A report identified a cipher suite that is not supported.
+  TCN_STATUS_UNSUPPORTED_CIPHER_SUITE = 20,
+  // A report did not carry a valid health authority verification token.
+  TCN_STATUS_INVALID_VERIFICATION_TOKEN = 21,
+  // A report's verification token had already been redeemed.
+  TCN_STATUS_TOKEN_ALREADY_REDEEMED = 22,
+  // A blind authorization request, response or report authorization was
+  // invalid.
+  TCN_STATUS_BLIND_AUTHORIZATION_FAILED = 23,
+  // A private set intersection query or response was malformed.
+  TCN_STATUS_INVALID_PSI_MESSAGE = 24,
+  // A TCN could not be split into, or reconstructed from, the given
+  // number of shares with the given threshold.
+  TCN_STATUS_INVALID_SHARE_THRESHOLD = 25,
+  // A TCN share repeated an index, had index 0, or disagreed with the
+  // other shares on the tag or threshold.
+  TCN_STATUS_INVALID_SHARE = 26,
+  // A required pointer argument was null.
+  TCN_STATUS_NULL_POINTER = 100,
+  // The temporary contact key ratchet has reached its last index.
+  TCN_STATUS_RATCHET_EXHAUSTED = 101,
+} TcnStatus;
+
+// An opaque handle to a verified report.
+typedef struct TcnReport TcnReport;
+
+// An opaque handle to a secret report authorization key.
+typedef struct TcnReportAuthorizationKey TcnReportAuthorizationKey;
+
+// An opaque handle to a temporary contact key, the secret ratchet state.
+typedef struct TcnTemporaryContactKey TcnTemporaryContactKey;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Return a static, nul-terminated description of a status code.
+//
+// Codes that are not `TcnStatus` values are described as unknown.
+const char *tcn_status_message(uint32_t status);
+
+// Generate a new report authorization key using the operating system's
+// random number generator.
+//
+// # Safety
+//
+// `out_rak` must be valid for writes.
+enum TcnStatus tcn_rak_new(struct TcnReportAuthorizationKey **out_rak);
+
+// Import a **secret** report authorization key from its 32-byte encoding.
+//
+// # Safety
+//
+// `data` must be valid for `len` bytes and `out_rak` must be valid for writes.
+enum TcnStatus tcn_rak_from_bytes(const uint8_t *data,
+                                  size_t len,
+                                  struct TcnReportAuthorizationKey **out_rak);
+
+// Export the **secret** report authorization key as `TCN_RAK_LEN` bytes, for
+// secure storage.
+//
+// # Safety
+//
+// `rak` must be a live handle, `out` must be valid for `*out_len` bytes and
+// `out_len` must be valid for reads and writes.
+enum TcnStatus tcn_rak_to_bytes(const struct TcnReportAuthorizationKey *rak,
+                                uint8_t *out,
+                                size_t *out_len);
+
+// Compute the initial temporary contact key `tck_1` of a report
+// authorization key.
+//
+// # Safety
+//
+// `rak` must be a live handle and `out_tck` must be valid for writes.
+enum TcnStatus tcn_rak_initial_tck(const struct TcnReportAuthorizationKey *rak,
+                                   struct TcnTemporaryContactKey **out_tck);
+
+// Create a signed report disclosing the TCNs with indices `j_1..=j_2`,
+// writing its serialization into `out`.
+//
+// # Safety
+//
+// `rak` must be a live handle, `memo_data` must be valid for `memo_len`
+// bytes, `out` must be valid for `*out_len` bytes and `out_len` must be valid
+// for reads and writes.
+enum TcnStatus tcn_rak_create_report(const struct TcnReportAuthorizationKey *rak,
+                                     uint8_t memo_type,
+                                     const uint8_t *memo_data,
+                                     size_t memo_len,
+                                     uint16_t j_1,
+                                     uint16_t j_2,
+                                     uint8_t *out,
+                                     size_t *out_len);
+
+// Release a report authorization key, zeroizing it.
+//
+// # Safety
+//
+// `rak` must be null or a live handle, which must not be used afterwards.
+void tcn_rak_free(struct TcnReportAuthorizationKey *rak);
+
+// Import a temporary contact key from its `TCN_TCK_LEN`-byte encoding.
+//
+// # Safety
+//
+// `data` must be valid for `len` bytes and `out_tck` must be valid for writes.
+enum TcnStatus tcn_tck_from_bytes(const uint8_t *data,
+                                  size_t len,
+                                  struct TcnTemporaryContactKey **out_tck);
+
+// Export a temporary contact key as `TCN_TCK_LEN` bytes, for secure storage.
+//
+// # Safety
+//
+// `tck` must be a live handle, `out` must be valid for `*out_len` bytes and
+// `out_len` must be valid for reads and writes.
+enum TcnStatus tcn_tck_to_bytes(const struct TcnTemporaryContactKey *tck,
+                                uint8_t *out,
+                                size_t *out_len);
+
+// Return the index of a temporary contact key, or 0 if `tck` is null.
+//
+// # Safety
+//
+// `tck` must be null or a live handle.
+uint16_t tcn_tck_index(const struct TcnTemporaryContactKey *tck);
+
+// Write the `TCN_TCN_LEN`-byte temporary contact number derived from a
+// temporary contact key into `out`.
+//
+// # Safety
+//
+// `tck` must be a live handle and `out` must be valid for `TCN_TCN_LEN` bytes.
+enum TcnStatus tcn_tck_tcn(const struct TcnTemporaryContactKey *tck, uint8_t *out);
+
+// Advance a temporary contact key to the next index in place.
+//
+// Returns `TCN_STATUS_RATCHET_EXHAUSTED`, leaving the key unchanged, once the
+// last index has been reached; the report authorization key should then be
+// rotated.
+//
+// # Safety
+//
+// `tck` must be a live handle.
+enum TcnStatus tcn_tck_ratchet(struct TcnTemporaryContactKey *tck);
+
+// Release a temporary contact key, zeroizing it.
+//
+// # Safety
+//
+// `tck` must be null or a live handle, which must not be used afterwards.
+void tcn_tck_free(struct TcnTemporaryContactKey *tck);
+
+// Parse and verify a serialized signed report, which must make up the whole
+// of `data`.
+//
+// On success, `out_report` receives a handle to the verified report.
+//
+// # Safety
+//
+// `data` must be valid for `len` bytes and `out_report` must be valid for
+// writes.
+enum TcnStatus tcn_signed_report_verify(const uint8_t *data,
+                                        size_t len,
+                                        struct TcnReport **out_report);
+
+// Serialize a verified report, without its signature, into `out`.
+//
+// # Safety
+//
+// `report` must be a live handle, `out` must be valid for `*out_len` bytes
+// and `out_len` must be valid for reads and writes.
+enum TcnStatus tcn_report_to_bytes(const struct TcnReport *report, uint8_t *out, size_t *out_len);
+
+// Get the memo type of a report.
+//
+// # Safety
+//
+// `report` must be a live handle and `out_memo_type` must be valid for
+// writes.
+enum TcnStatus tcn_report_memo_type(const struct TcnReport *report, uint8_t *out_memo_type);
+
+// Borrow the memo data of a report.
+//
+// The returned pointer remains valid until the report is freed.
+//
+// # Safety
+//
+// `report` must be a live handle and `out_data` and `out_len` must be valid
+// for writes.
+enum TcnStatus tcn_report_memo_data(const struct TcnReport *report,
+                                    const uint8_t **out_data,
+                                    size_t *out_len);
+
+// Recompute the temporary contact numbers disclosed by a report, writing
+// them packed into `out`.
+//
+// `*out_count` holds the capacity of `out` in TCNs and receives the number
+// of TCNs written or, on `TCN_STATUS_BUFFER_TOO_SMALL`, needed.
+//
+// # Safety
+//
+// `report` must be a live handle, `out` must be valid for
+// `*out_count * TCN_TCN_LEN` bytes and `out_count` must be valid for reads
+// and writes.
+enum TcnStatus tcn_report_tcns(const struct TcnReport *report, uint8_t *out, size_t *out_count);
+
+// Match locally observed temporary contact numbers against a report.
+//
+// `observed` holds `count` packed TCNs; for each one, the corresponding entry
+// of `out_matched` is set to whether the report discloses it.
+//
+// # Safety
+//
+// `report` must be a live handle, `observed` must be valid for
+// `count * TCN_TCN_LEN` bytes and `out_matched` must be valid for `count`
+// writes.
+enum TcnStatus tcn_report_match(const struct TcnReport *report,
+                                const uint8_t *observed,
+                                size_t count,
+                                bool *out_matched);
+
+// Release a report.
+//
+// # Safety
+//
+// `report` must be null or a live handle, which must not be used afterwards.
+void tcn_report_free(struct TcnReport *report);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TCN_H */
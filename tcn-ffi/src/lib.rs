@@ -0,0 +1,676 @@
+//! A stable C ABI for the [`tcn`] crate, so that mobile and embedded apps can
+//! share the reference implementation instead of re-implementing the protocol.
+//!
+//! The C header `include/tcn.h` is generated from this crate with
+//! [cbindgen](https://github.com/eqrion/cbindgen); after changing the API,
+//! regenerate it with
+//! ```text
+//! TCN_FFI_BLESS=1 cargo test -p tcn-ffi
+//! ```
+//!
+//! # Conventions
+//!
+//! - Secret keys and ratchet state are held in opaque handles, which are
+//!   zeroized when released with the matching `_free` function.
+//! - Every fallible function returns a [`TcnStatus`], writing its results
+//!   through out-pointers only on success.
+//! - Byte buffers are passed as a pointer and a length.  Functions that write
+//!   variable-length output take the buffer capacity and report the number of
+//!   bytes written (or, on `TCN_STATUS_BUFFER_TOO_SMALL`, the number of bytes
+//!   needed) through `out_len`.
+//! - Temporary contact numbers are passed as packed 16-byte values.
+//!
+//! # Safety
+//!
+//! Callers must pass pointers that are either null or valid for the documented
+//! lengths, and must not use a handle after freeing it.  Null pointers are
+//! rejected with `TCN_STATUS_NULL_POINTER`.
+
+#![deny(missing_docs)]
+
+use std::{collections::HashSet, convert::TryInto, os::raw::c_char, slice};
+
+use rand_core::OsRng;
+use tcn::{Error, Report, ReportAuthorizationKey, SignedReportRef, TemporaryContactKey};
+
+/// The length of a serialized report authorization key.
+pub const TCN_RAK_LEN: usize = 32;
+/// The length of a serialized temporary contact key.
+pub const TCN_TCK_LEN: usize = 66;
+/// The length of a temporary contact number.
+pub const TCN_TCN_LEN: usize = 16;
+/// The largest possible length of a serialized signed report.
+pub const TCN_SIGNED_REPORT_MAX_LEN: usize = 74 + 65535 + 64;
+
+/// Status codes returned by the C API.
+///
+/// Codes other than `TCN_STATUS_OK` below 100 correspond to the variants of
+/// `tcn::Error`; codes from 100 are specific to the C API.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TcnStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// An unknown memo type was encountered while parsing a report.
+    UnknownMemoType = 1,
+    /// Reports cannot include the TCN with index 0.
+    InvalidReportIndex = 2,
+    /// An underlying I/O error occurred while parsing data.
+    Io = 3,
+    /// An oversized memo field was supplied when creating a report.
+    OversizeMemo = 4,
+    /// A report failed the source integrity check.
+    ReportVerificationFailed = 5,
+    /// A hex string was invalid.
+    InvalidHex = 6,
+    /// A base64 string was invalid.
+    InvalidBase64 = 7,
+    /// Decoded data had the wrong length for the value being parsed.
+    InvalidLength = 8,
+    /// An output buffer was too small for the value being encoded.
+    BufferTooSmall = 9,
+    /// A key storage container had an unsupported format version.
+    UnsupportedStorageVersion = 10,
+    /// A key storage container was malformed.
+    InvalidStorageContainer = 11,
+    /// A key storage container could not be decrypted.
+    StorageDecryptionFailed = 12,
+    /// A report's index range was empty, with `j_2 < j_1`.
+    ReportRangeInverted = 13,
+    /// A report disclosed more TCNs than allowed by a report policy.
+    ReportRangeTooLarge = 14,
+    /// A report's memo type was not allowed by a report policy.
+    MemoTypeNotAllowed = 15,
+    /// A report's memo was longer than allowed by a report policy.
+    MemoTooLong = 16,
+    /// A report's memo did not satisfy the schema of its memo type.
+    InvalidMemo = 17,
+    /// A report bundle failed the publisher signature check.
+    BundleVerificationFailed = 18,
+    /// A report used a wire format version that is not supported.
+    UnsupportedReportVersion = 19,
+    /// A report identified a cipher suite that is not supported.
+    UnsupportedCipherSuite = 20,
+    /// A report did not carry a valid health authority verification token.
+    InvalidVerificationToken = 21,
+    /// A report's verification token had already been redeemed.
+    TokenAlreadyRedeemed = 22,
+    /// A blind authorization request, response or report authorization was
+    /// invalid.
+    BlindAuthorizationFailed = 23,
+    /// A private set intersection query or response was malformed.
+    InvalidPsiMessage = 24,
+    /// A TCN could not be split into, or reconstructed from, the given
+    /// number of shares with the given threshold.
+    InvalidShareThreshold = 25,
+    /// A TCN share repeated an index, had index 0, or disagreed with the
+    /// other shares on the tag or threshold.
+    InvalidShare = 26,
+    /// A required pointer argument was null.
+    NullPointer = 100,
+    /// The temporary contact key ratchet has reached its last index.
+    RatchetExhausted = 101,
+}
+
+impl TcnStatus {
+    /// Every status, in code order.
+    const ALL: [TcnStatus; 29] = [
+        TcnStatus::Ok,
+        TcnStatus::UnknownMemoType,
+        TcnStatus::InvalidReportIndex,
+        TcnStatus::Io,
+        TcnStatus::OversizeMemo,
+        TcnStatus::ReportVerificationFailed,
+        TcnStatus::InvalidHex,
+        TcnStatus::InvalidBase64,
+        TcnStatus::InvalidLength,
+        TcnStatus::BufferTooSmall,
+        TcnStatus::UnsupportedStorageVersion,
+        TcnStatus::InvalidStorageContainer,
+        TcnStatus::StorageDecryptionFailed,
+        TcnStatus::ReportRangeInverted,
+        TcnStatus::ReportRangeTooLarge,
+        TcnStatus::MemoTypeNotAllowed,
+        TcnStatus::MemoTooLong,
+        TcnStatus::InvalidMemo,
+        TcnStatus::BundleVerificationFailed,
+        TcnStatus::UnsupportedReportVersion,
+        TcnStatus::UnsupportedCipherSuite,
+        TcnStatus::InvalidVerificationToken,
+        TcnStatus::TokenAlreadyRedeemed,
+        TcnStatus::BlindAuthorizationFailed,
+        TcnStatus::InvalidPsiMessage,
+        TcnStatus::InvalidShareThreshold,
+        TcnStatus::InvalidShare,
+        TcnStatus::NullPointer,
+        TcnStatus::RatchetExhausted,
+    ];
+
+    /// Look up the status with the given code, which may come from C as any
+    /// integer.
+    fn from_code(code: u32) -> Option<TcnStatus> {
+        TcnStatus::ALL
+            .iter()
+            .copied()
+            .find(|&status| status as u32 == code)
+    }
+}
+
+impl From<Error> for TcnStatus {
+    fn from(error: Error) -> TcnStatus {
+        match error {
+            Error::UnknownMemoType(_) => TcnStatus::UnknownMemoType,
+            Error::InvalidReportIndex => TcnStatus::InvalidReportIndex,
+            Error::Io(_) => TcnStatus::Io,
+            Error::OversizeMemo(_) => TcnStatus::OversizeMemo,
+            Error::ReportVerificationFailed => TcnStatus::ReportVerificationFailed,
+            Error::InvalidHex => TcnStatus::InvalidHex,
+            Error::InvalidBase64 => TcnStatus::InvalidBase64,
+            Error::InvalidLength(_) => TcnStatus::InvalidLength,
+            Error::BufferTooSmall(_) => TcnStatus::BufferTooSmall,
+            Error::UnsupportedStorageVersion(_) => TcnStatus::UnsupportedStorageVersion,
+            Error::InvalidStorageContainer => TcnStatus::InvalidStorageContainer,
+            Error::StorageDecryptionFailed => TcnStatus::StorageDecryptionFailed,
+            Error::ReportRangeInverted(..) => TcnStatus::ReportRangeInverted,
+            Error::ReportRangeTooLarge(_) => TcnStatus::ReportRangeTooLarge,
+            Error::MemoTypeNotAllowed(_) => TcnStatus::MemoTypeNotAllowed,
+            Error::MemoTooLong(_) => TcnStatus::MemoTooLong,
+            Error::InvalidMemo(_) => TcnStatus::InvalidMemo,
+            Error::BundleVerificationFailed => TcnStatus::BundleVerificationFailed,
+            Error::UnsupportedReportVersion(_) => TcnStatus::UnsupportedReportVersion,
+            Error::UnsupportedCipherSuite(_) => TcnStatus::UnsupportedCipherSuite,
+            Error::InvalidVerificationToken => TcnStatus::InvalidVerificationToken,
+            Error::TokenAlreadyRedeemed => TcnStatus::TokenAlreadyRedeemed,
+            Error::BlindAuthorizationFailed => TcnStatus::BlindAuthorizationFailed,
+            Error::InvalidPsiMessage => TcnStatus::InvalidPsiMessage,
+            Error::InvalidShareThreshold(..) => TcnStatus::InvalidShareThreshold,
+            Error::InvalidShare(_) => TcnStatus::InvalidShare,
+        }
+    }
+}
+
+/// An opaque handle to a secret report authorization key.
+pub struct TcnReportAuthorizationKey(ReportAuthorizationKey);
+
+/// An opaque handle to a temporary contact key, the secret ratchet state.
+pub struct TcnTemporaryContactKey(TemporaryContactKey);
+
+/// An opaque handle to a verified report.
+pub struct TcnReport(Report);
+
+/// Borrow `len` bytes starting at `data`, allowing a null pointer only when
+/// `len` is zero.
+unsafe fn input<'a>(data: *const u8, len: usize) -> Result<&'a [u8], TcnStatus> {
+    match (data.is_null(), len) {
+        (true, 0) => Ok(&[]),
+        (true, _) => Err(TcnStatus::NullPointer),
+        (false, _) => Ok(slice::from_raw_parts(data, len)),
+    }
+}
+
+/// Borrow `len` writable bytes starting at `out`.
+unsafe fn output<'a>(out: *mut u8, len: usize) -> Result<&'a mut [u8], TcnStatus> {
+    match (out.is_null(), len) {
+        (true, 0) => Ok(&mut []),
+        (true, _) => Err(TcnStatus::NullPointer),
+        (false, _) => Ok(slice::from_raw_parts_mut(out, len)),
+    }
+}
+
+/// Borrow the value behind a handle.
+unsafe fn handle<'a, T>(ptr: *const T) -> Result<&'a T, TcnStatus> {
+    ptr.as_ref().ok_or(TcnStatus::NullPointer)
+}
+
+/// Write `value` through an out-pointer.
+unsafe fn put<T>(out: *mut T, value: T) -> Result<(), TcnStatus> {
+    if out.is_null() {
+        return Err(TcnStatus::NullPointer);
+    }
+    out.write(value);
+    Ok(())
+}
+
+/// Run `f`, encoding into a caller buffer of capacity `*out_len` and storing
+/// the number of bytes written, or needed, back into `out_len`.
+unsafe fn encode_into<F>(out: *mut u8, out_len: *mut usize, f: F) -> Result<(), TcnStatus>
+where
+    F: FnOnce(&mut [u8]) -> Result<usize, Error>,
+{
+    let capacity = *out_len.as_ref().ok_or(TcnStatus::NullPointer)?;
+    match f(output(out, capacity)?) {
+        Ok(written) => {
+            *out_len = written;
+            Ok(())
+        }
+        Err(Error::BufferTooSmall(needed)) => {
+            *out_len = needed;
+            Err(TcnStatus::BufferTooSmall)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn status(result: Result<(), TcnStatus>) -> TcnStatus {
+    match result {
+        Ok(()) => TcnStatus::Ok,
+        Err(status) => status,
+    }
+}
+
+/// Return a static, nul-terminated description of a status code.
+///
+/// Codes that are not `TcnStatus` values are described as unknown.
+#[no_mangle]
+pub extern "C" fn tcn_status_message(status: u32) -> *const c_char {
+    let message: &'static [u8] = match TcnStatus::from_code(status) {
+        Some(TcnStatus::Ok) => b"Success\0",
+        Some(TcnStatus::UnknownMemoType) => b"Unknown memo type\0",
+        Some(TcnStatus::InvalidReportIndex) => b"Invalid TCN index in report\0",
+        Some(TcnStatus::Io) => b"I/O error\0",
+        Some(TcnStatus::OversizeMemo) => b"Oversize memo field\0",
+        Some(TcnStatus::ReportVerificationFailed) => b"Report verification failed\0",
+        Some(TcnStatus::InvalidHex) => b"Invalid hex encoding\0",
+        Some(TcnStatus::InvalidBase64) => b"Invalid base64 encoding\0",
+        Some(TcnStatus::InvalidLength) => b"Invalid encoded length\0",
+        Some(TcnStatus::BufferTooSmall) => b"Output buffer too small\0",
+        Some(TcnStatus::UnsupportedStorageVersion) => b"Unsupported key storage version\0",
+        Some(TcnStatus::InvalidStorageContainer) => b"Invalid key storage container\0",
+        Some(TcnStatus::StorageDecryptionFailed) => b"Key storage decryption failed\0",
+        Some(TcnStatus::ReportRangeInverted) => b"Report index range is inverted\0",
+        Some(TcnStatus::ReportRangeTooLarge) => b"Report discloses too many TCNs\0",
+        Some(TcnStatus::MemoTypeNotAllowed) => b"Memo type not allowed\0",
+        Some(TcnStatus::MemoTooLong) => b"Memo too long\0",
+        Some(TcnStatus::InvalidMemo) => b"Invalid memo\0",
+        Some(TcnStatus::BundleVerificationFailed) => b"Report bundle verification failed\0",
+        Some(TcnStatus::UnsupportedReportVersion) => b"Unsupported report version\0",
+        Some(TcnStatus::UnsupportedCipherSuite) => b"Unsupported cipher suite\0",
+        Some(TcnStatus::InvalidVerificationToken) => b"Invalid verification token\0",
+        Some(TcnStatus::TokenAlreadyRedeemed) => b"Verification token already redeemed\0",
+        Some(TcnStatus::BlindAuthorizationFailed) => b"Blind authorization failed\0",
+        Some(TcnStatus::InvalidPsiMessage) => b"Invalid private set intersection message\0",
+        Some(TcnStatus::InvalidShareThreshold) => b"Invalid share threshold\0",
+        Some(TcnStatus::InvalidShare) => b"Invalid TCN share\0",
+        Some(TcnStatus::NullPointer) => b"Null pointer argument\0",
+        Some(TcnStatus::RatchetExhausted) => b"Temporary contact key ratchet exhausted\0",
+        None => b"Unknown status\0",
+    };
+    message.as_ptr() as *const c_char
+}
+
+/// Generate a new report authorization key using the operating system's
+/// random number generator.
+///
+/// # Safety
+///
+/// `out_rak` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_rak_new(out_rak: *mut *mut TcnReportAuthorizationKey) -> TcnStatus {
+    let rak = Box::new(TcnReportAuthorizationKey(ReportAuthorizationKey::new(
+        OsRng,
+    )));
+    status(put(out_rak, Box::into_raw(rak)))
+}
+
+/// Import a **secret** report authorization key from its 32-byte encoding.
+///
+/// # Safety
+///
+/// `data` must be valid for `len` bytes and `out_rak` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_rak_from_bytes(
+    data: *const u8,
+    len: usize,
+    out_rak: *mut *mut TcnReportAuthorizationKey,
+) -> TcnStatus {
+    status((|| {
+        let rak = ReportAuthorizationKey::decode_from_slice(input(data, len)?)?;
+        put(
+            out_rak,
+            Box::into_raw(Box::new(TcnReportAuthorizationKey(rak))),
+        )
+    })())
+}
+
+/// Export the **secret** report authorization key as `TCN_RAK_LEN` bytes, for
+/// secure storage.
+///
+/// # Safety
+///
+/// `rak` must be a live handle, `out` must be valid for `*out_len` bytes and
+/// `out_len` must be valid for reads and writes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_rak_to_bytes(
+    rak: *const TcnReportAuthorizationKey,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> TcnStatus {
+    status((|| {
+        let rak = &handle(rak)?.0;
+        encode_into(out, out_len, |out| rak.encode_to_slice(out))
+    })())
+}
+
+/// Compute the initial temporary contact key `tck_1` of a report
+/// authorization key.
+///
+/// # Safety
+///
+/// `rak` must be a live handle and `out_tck` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_rak_initial_tck(
+    rak: *const TcnReportAuthorizationKey,
+    out_tck: *mut *mut TcnTemporaryContactKey,
+) -> TcnStatus {
+    status((|| {
+        let tck = handle(rak)?.0.initial_temporary_contact_key();
+        put(
+            out_tck,
+            Box::into_raw(Box::new(TcnTemporaryContactKey(tck))),
+        )
+    })())
+}
+
+/// Create a signed report disclosing the TCNs with indices `j_1..=j_2`,
+/// writing its serialization into `out`.
+///
+/// # Safety
+///
+/// `rak` must be a live handle, `memo_data` must be valid for `memo_len`
+/// bytes, `out` must be valid for `*out_len` bytes and `out_len` must be valid
+/// for reads and writes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_rak_create_report(
+    rak: *const TcnReportAuthorizationKey,
+    memo_type: u8,
+    memo_data: *const u8,
+    memo_len: usize,
+    j_1: u16,
+    j_2: u16,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> TcnStatus {
+    status((|| {
+        let rak = &handle(rak)?.0;
+        let memo_type = memo_type.try_into()?;
+        let memo_data = input(memo_data, memo_len)?;
+        encode_into(out, out_len, |out| {
+            rak.create_report_into(memo_type, memo_data, j_1, j_2, out)
+                .map(|signed_report| signed_report.encoded_len())
+        })
+    })())
+}
+
+/// Release a report authorization key, zeroizing it.
+///
+/// # Safety
+///
+/// `rak` must be null or a live handle, which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_rak_free(rak: *mut TcnReportAuthorizationKey) {
+    if !rak.is_null() {
+        drop(Box::from_raw(rak));
+    }
+}
+
+/// Import a temporary contact key from its `TCN_TCK_LEN`-byte encoding.
+///
+/// # Safety
+///
+/// `data` must be valid for `len` bytes and `out_tck` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_tck_from_bytes(
+    data: *const u8,
+    len: usize,
+    out_tck: *mut *mut TcnTemporaryContactKey,
+) -> TcnStatus {
+    status((|| {
+        let tck = TemporaryContactKey::decode_from_slice(input(data, len)?)?;
+        put(
+            out_tck,
+            Box::into_raw(Box::new(TcnTemporaryContactKey(tck))),
+        )
+    })())
+}
+
+/// Export a temporary contact key as `TCN_TCK_LEN` bytes, for secure storage.
+///
+/// # Safety
+///
+/// `tck` must be a live handle, `out` must be valid for `*out_len` bytes and
+/// `out_len` must be valid for reads and writes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_tck_to_bytes(
+    tck: *const TcnTemporaryContactKey,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> TcnStatus {
+    status((|| {
+        let tck = &handle(tck)?.0;
+        encode_into(out, out_len, |out| tck.encode_to_slice(out))
+    })())
+}
+
+/// Return the index of a temporary contact key, or 0 if `tck` is null.
+///
+/// # Safety
+///
+/// `tck` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_tck_index(tck: *const TcnTemporaryContactKey) -> u16 {
+    handle(tck).map(|tck| tck.0.index()).unwrap_or(0)
+}
+
+/// Write the `TCN_TCN_LEN`-byte temporary contact number derived from a
+/// temporary contact key into `out`.
+///
+/// # Safety
+///
+/// `tck` must be a live handle and `out` must be valid for `TCN_TCN_LEN` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_tck_tcn(
+    tck: *const TcnTemporaryContactKey,
+    out: *mut u8,
+) -> TcnStatus {
+    status((|| {
+        let tcn = handle(tck)?.0.temporary_contact_number();
+        output(out, TCN_TCN_LEN)?.copy_from_slice(&tcn.0);
+        Ok(())
+    })())
+}
+
+/// Advance a temporary contact key to the next index in place.
+///
+/// Returns `TCN_STATUS_RATCHET_EXHAUSTED`, leaving the key unchanged, once the
+/// last index has been reached; the report authorization key should then be
+/// rotated.
+///
+/// # Safety
+///
+/// `tck` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_tck_ratchet(tck: *mut TcnTemporaryContactKey) -> TcnStatus {
+    status((|| {
+        let tck = tck.as_mut().ok_or(TcnStatus::NullPointer)?;
+        tck.0 = tck.0.clone().ratchet().ok_or(TcnStatus::RatchetExhausted)?;
+        Ok(())
+    })())
+}
+
+/// Release a temporary contact key, zeroizing it.
+///
+/// # Safety
+///
+/// `tck` must be null or a live handle, which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_tck_free(tck: *mut TcnTemporaryContactKey) {
+    if !tck.is_null() {
+        drop(Box::from_raw(tck));
+    }
+}
+
+/// Parse and verify a serialized signed report, which must make up the whole
+/// of `data`.
+///
+/// On success, `out_report` receives a handle to the verified report.
+///
+/// # Safety
+///
+/// `data` must be valid for `len` bytes and `out_report` must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_signed_report_verify(
+    data: *const u8,
+    len: usize,
+    out_report: *mut *mut TcnReport,
+) -> TcnStatus {
+    status((|| {
+        let data = input(data, len)?;
+        let report = match SignedReportRef::parse(data)? {
+            (signed_report, []) => signed_report.verify()?.to_report(),
+            _ => return Err(TcnStatus::InvalidLength),
+        };
+        put(out_report, Box::into_raw(Box::new(TcnReport(report))))
+    })())
+}
+
+/// Serialize a verified report, without its signature, into `out`.
+///
+/// # Safety
+///
+/// `report` must be a live handle, `out` must be valid for `*out_len` bytes
+/// and `out_len` must be valid for reads and writes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_report_to_bytes(
+    report: *const TcnReport,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> TcnStatus {
+    status((|| {
+        let report = &handle(report)?.0;
+        encode_into(out, out_len, |out| report.encode_to_slice(out))
+    })())
+}
+
+/// Get the memo type of a report.
+///
+/// # Safety
+///
+/// `report` must be a live handle and `out_memo_type` must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_report_memo_type(
+    report: *const TcnReport,
+    out_memo_type: *mut u8,
+) -> TcnStatus {
+    status((|| put(out_memo_type, handle(report)?.0.memo_type() as u8))())
+}
+
+/// Borrow the memo data of a report.
+///
+/// The returned pointer remains valid until the report is freed.
+///
+/// # Safety
+///
+/// `report` must be a live handle and `out_data` and `out_len` must be valid
+/// for writes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_report_memo_data(
+    report: *const TcnReport,
+    out_data: *mut *const u8,
+    out_len: *mut usize,
+) -> TcnStatus {
+    status((|| {
+        let memo_data = handle(report)?.0.memo_data();
+        put(out_data, memo_data.as_ptr())?;
+        put(out_len, memo_data.len())
+    })())
+}
+
+/// Recompute the temporary contact numbers disclosed by a report, writing
+/// them packed into `out`.
+///
+/// `*out_count` holds the capacity of `out` in TCNs and receives the number
+/// of TCNs written or, on `TCN_STATUS_BUFFER_TOO_SMALL`, needed.
+///
+/// # Safety
+///
+/// `report` must be a live handle, `out` must be valid for
+/// `*out_count * TCN_TCN_LEN` bytes and `out_count` must be valid for reads
+/// and writes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_report_tcns(
+    report: *const TcnReport,
+    out: *mut u8,
+    out_count: *mut usize,
+) -> TcnStatus {
+    status((|| {
+        let report = &handle(report)?.0;
+        let capacity = *out_count.as_ref().ok_or(TcnStatus::NullPointer)?;
+        let out = output(out, capacity.saturating_mul(TCN_TCN_LEN))?;
+        let mut count = 0;
+        for tcn in report.temporary_contact_numbers() {
+            if let Some(chunk) = out.get_mut(count * TCN_TCN_LEN..(count + 1) * TCN_TCN_LEN) {
+                chunk.copy_from_slice(&tcn.0);
+            }
+            count += 1;
+        }
+        *out_count = count;
+        if count > capacity {
+            return Err(TcnStatus::BufferTooSmall);
+        }
+        Ok(())
+    })())
+}
+
+/// Match locally observed temporary contact numbers against a report.
+///
+/// `observed` holds `count` packed TCNs; for each one, the corresponding entry
+/// of `out_matched` is set to whether the report discloses it.
+///
+/// # Safety
+///
+/// `report` must be a live handle, `observed` must be valid for
+/// `count * TCN_TCN_LEN` bytes and `out_matched` must be valid for `count`
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_report_match(
+    report: *const TcnReport,
+    observed: *const u8,
+    count: usize,
+    out_matched: *mut bool,
+) -> TcnStatus {
+    status((|| {
+        let report = &handle(report)?.0;
+        let len = count
+            .checked_mul(TCN_TCN_LEN)
+            .ok_or(TcnStatus::InvalidLength)?;
+        let observed = input(observed, len)?;
+        let out_matched = match (out_matched.is_null(), count) {
+            (true, 0) => &mut [],
+            (true, _) => return Err(TcnStatus::NullPointer),
+            (false, _) => slice::from_raw_parts_mut(out_matched, count),
+        };
+
+        let reported = report
+            .temporary_contact_numbers()
+            .map(|tcn| tcn.0)
+            .collect::<HashSet<[u8; TCN_TCN_LEN]>>();
+        for (tcn, matched) in observed.chunks_exact(TCN_TCN_LEN).zip(out_matched) {
+            *matched = reported.contains(tcn);
+        }
+        Ok(())
+    })())
+}
+
+/// Release a report.
+///
+/// # Safety
+///
+/// `report` must be null or a live handle, which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn tcn_report_free(report: *mut TcnReport) {
+    if !report.is_null() {
+        drop(Box::from_raw(report));
+    }
+}
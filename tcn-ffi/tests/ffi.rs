@@ -0,0 +1,195 @@
+use std::{ffi::CStr, ptr};
+
+use tcn_ffi::*;
+
+#[test]
+fn c_api_round_trip() {
+    unsafe {
+        let mut rak = ptr::null_mut();
+        assert_eq!(tcn_rak_new(&mut rak), TcnStatus::Ok);
+
+        // Export and re-import the secret key.
+        let mut rak_bytes = [0; TCN_RAK_LEN];
+        let mut len = rak_bytes.len();
+        assert_eq!(
+            tcn_rak_to_bytes(rak, rak_bytes.as_mut_ptr(), &mut len),
+            TcnStatus::Ok
+        );
+        assert_eq!(len, TCN_RAK_LEN);
+        let mut restored = ptr::null_mut();
+        assert_eq!(
+            tcn_rak_from_bytes(rak_bytes.as_ptr(), len, &mut restored),
+            TcnStatus::Ok
+        );
+        tcn_rak_free(rak);
+
+        // Generate TCNs, round-tripping the ratchet state along the way.
+        let mut tck = ptr::null_mut();
+        assert_eq!(tcn_rak_initial_tck(restored, &mut tck), TcnStatus::Ok);
+        let mut tcns = Vec::new();
+        for i in 1..=20 {
+            assert_eq!(tcn_tck_index(tck), i);
+            let mut tcn = [0; TCN_TCN_LEN];
+            assert_eq!(tcn_tck_tcn(tck, tcn.as_mut_ptr()), TcnStatus::Ok);
+            tcns.push(tcn);
+
+            let mut tck_bytes = [0; TCN_TCK_LEN];
+            let mut len = tck_bytes.len();
+            assert_eq!(
+                tcn_tck_to_bytes(tck, tck_bytes.as_mut_ptr(), &mut len),
+                TcnStatus::Ok
+            );
+            tcn_tck_free(tck);
+            assert_eq!(
+                tcn_tck_from_bytes(tck_bytes.as_ptr(), len, &mut tck),
+                TcnStatus::Ok
+            );
+            assert_eq!(tcn_tck_ratchet(tck), TcnStatus::Ok);
+        }
+        tcn_tck_free(tck);
+
+        // Create a report, reporting the needed size on a short buffer.
+        let memo = b"symptom data";
+        let mut signed_report = [0; TCN_SIGNED_REPORT_MAX_LEN];
+        let mut len = 10;
+        assert_eq!(
+            tcn_rak_create_report(
+                restored,
+                0,
+                memo.as_ptr(),
+                memo.len(),
+                5,
+                15,
+                signed_report.as_mut_ptr(),
+                &mut len
+            ),
+            TcnStatus::BufferTooSmall
+        );
+        assert_eq!(len, 70 + memo.len() + 64);
+        assert_eq!(
+            tcn_rak_create_report(
+                restored,
+                0,
+                memo.as_ptr(),
+                memo.len(),
+                5,
+                15,
+                signed_report.as_mut_ptr(),
+                &mut len
+            ),
+            TcnStatus::Ok
+        );
+        tcn_rak_free(restored);
+
+        // Verify the report and inspect its contents.
+        let mut report = ptr::null_mut();
+        assert_eq!(
+            tcn_signed_report_verify(signed_report.as_ptr(), len, &mut report),
+            TcnStatus::Ok
+        );
+        let mut memo_type = 0xff;
+        assert_eq!(tcn_report_memo_type(report, &mut memo_type), TcnStatus::Ok);
+        assert_eq!(memo_type, 0);
+        let (mut data, mut data_len) = (ptr::null(), 0);
+        assert_eq!(
+            tcn_report_memo_data(report, &mut data, &mut data_len),
+            TcnStatus::Ok
+        );
+        assert_eq!(std::slice::from_raw_parts(data, data_len), memo);
+
+        let mut recomputed = vec![0; 11 * TCN_TCN_LEN];
+        let mut count = 11;
+        assert_eq!(
+            tcn_report_tcns(report, recomputed.as_mut_ptr(), &mut count),
+            TcnStatus::Ok
+        );
+        assert_eq!(count, 11);
+        assert_eq!(recomputed, tcns[4..15].concat());
+
+        // Match the observed TCNs against the report.
+        let observed = tcns.concat();
+        let mut matched = vec![false; tcns.len()];
+        assert_eq!(
+            tcn_report_match(report, observed.as_ptr(), tcns.len(), matched.as_mut_ptr()),
+            TcnStatus::Ok
+        );
+        let expected = (1..=20).map(|i| (5..=15).contains(&i)).collect::<Vec<_>>();
+        assert_eq!(matched, expected);
+        tcn_report_free(report);
+
+        // Tampered reports are rejected.
+        signed_report[70] ^= 1;
+        let mut report = ptr::null_mut();
+        assert_eq!(
+            tcn_signed_report_verify(signed_report.as_ptr(), len, &mut report),
+            TcnStatus::ReportVerificationFailed
+        );
+        assert!(report.is_null());
+    }
+}
+
+#[test]
+fn c_api_errors() {
+    unsafe {
+        let mut rak = ptr::null_mut();
+        assert_eq!(
+            tcn_rak_from_bytes(ptr::null(), 32, &mut rak),
+            TcnStatus::NullPointer
+        );
+        assert_eq!(
+            tcn_rak_from_bytes([0; 31].as_ptr(), 31, &mut rak),
+            TcnStatus::InvalidLength
+        );
+        assert_eq!(tcn_rak_new(&mut rak), TcnStatus::Ok);
+
+        let mut out = [0; 512];
+        let mut len = out.len();
+        assert_eq!(
+            tcn_rak_create_report(rak, 7, ptr::null(), 0, 1, 2, out.as_mut_ptr(), &mut len),
+            TcnStatus::UnknownMemoType
+        );
+        let memo = [0; 256];
+        assert_eq!(
+            tcn_rak_create_report(
+                rak,
+                0,
+                memo.as_ptr(),
+                memo.len(),
+                1,
+                2,
+                out.as_mut_ptr(),
+                &mut len
+            ),
+            TcnStatus::OversizeMemo
+        );
+        // An oversize memo is reported even when the buffer is also too small.
+        let mut len = 16;
+        assert_eq!(
+            tcn_rak_create_report(
+                rak,
+                0,
+                memo.as_ptr(),
+                memo.len(),
+                1,
+                2,
+                out.as_mut_ptr(),
+                &mut len
+            ),
+            TcnStatus::OversizeMemo
+        );
+        tcn_rak_free(rak);
+
+        let message = |code| CStr::from_ptr(tcn_status_message(code)).to_str().unwrap();
+        assert_eq!(
+            message(TcnStatus::ReportVerificationFailed as u32),
+            "Report verification failed"
+        );
+        assert_eq!(
+            message(TcnStatus::RatchetExhausted as u32),
+            "Temporary contact key ratchet exhausted"
+        );
+        for &code in [27, 99, 102, u32::MAX].iter() {
+            assert_eq!(message(code), "Unknown status");
+        }
+    }
+}
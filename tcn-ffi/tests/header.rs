@@ -0,0 +1,25 @@
+use std::{env, fs, path::Path};
+
+// Run TCN_FFI_BLESS=1 cargo test -p tcn-ffi to regenerate the header.
+#[test]
+fn header_is_up_to_date() {
+    let crate_dir = env!("CARGO_MANIFEST_DIR");
+    let config = cbindgen::Config::from_root_or_default(crate_dir);
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("header generation should succeed")
+        .write(&mut generated);
+
+    let path = Path::new(crate_dir).join("include/tcn.h");
+    if env::var_os("TCN_FFI_BLESS").is_some() {
+        fs::write(&path, &generated).expect("writing the header should succeed");
+    }
+    let committed = fs::read(&path).unwrap_or_default();
+    assert!(
+        committed == generated,
+        "include/tcn.h is out of date; regenerate it with TCN_FFI_BLESS=1 cargo test -p tcn-ffi"
+    );
+}
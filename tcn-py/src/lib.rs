@@ -0,0 +1,294 @@
+//! Python bindings for the [`tcn`] crate, so that research simulations use the
+//! exact key derivation and report format of the reference implementation.
+//!
+//! Build and install the `tcn` Python module with
+//! [maturin](https://www.maturin.rs/):
+//! ```text
+//! cd tcn-py && maturin develop --release
+//! ```
+//!
+//! Keys, TCNs and serialized reports are exchanged as `bytes`, and errors are
+//! raised as `tcn.TcnError`, a subclass of `ValueError`.  The batch functions
+//! [`ReportAuthorizationKey::temporary_contact_numbers`] and
+//! [`expand_and_match`] run without holding the GIL.
+
+#![deny(missing_docs)]
+
+use std::{collections::HashSet, convert::TryInto};
+
+use pyo3::{create_exception, exceptions::PyValueError, prelude::*, types::PyBytes};
+use rand_core::OsRng;
+
+create_exception!(
+    tcn,
+    TcnError,
+    PyValueError,
+    "An error from the TCN protocol implementation."
+);
+
+fn tcn_error(e: tcn::Error) -> PyErr {
+    TcnError::new_err(e.to_string())
+}
+
+fn encode<F>(py: Python<'_>, len: usize, f: F) -> PyResult<Bound<'_, PyBytes>>
+where
+    F: FnOnce(&mut [u8]) -> Result<usize, tcn::Error>,
+{
+    let mut bytes = vec![0; len];
+    let len = f(&mut bytes).map_err(tcn_error)?;
+    Ok(PyBytes::new(py, &bytes[..len]))
+}
+
+fn tcn_bytes(tcn: tcn::TemporaryContactNumber, py: Python<'_>) -> Bound<'_, PyBytes> {
+    PyBytes::new(py, &tcn.0)
+}
+
+/// A secret report authorization key.
+#[pyclass(module = "tcn")]
+pub struct ReportAuthorizationKey(tcn::ReportAuthorizationKey);
+
+#[pymethods]
+impl ReportAuthorizationKey {
+    /// Generate a new report authorization key from the operating system's
+    /// random number generator.
+    #[new]
+    fn new() -> Self {
+        ReportAuthorizationKey(tcn::ReportAuthorizationKey::new(OsRng))
+    }
+
+    /// Import a report authorization key from its 32-byte secret encoding.
+    #[staticmethod]
+    fn from_secret_bytes(bytes: &[u8]) -> PyResult<Self> {
+        tcn::ReportAuthorizationKey::decode_from_slice(bytes)
+            .map(ReportAuthorizationKey)
+            .map_err(tcn_error)
+    }
+
+    /// Export the **secret** report authorization key as 32 bytes.
+    fn expose_secret_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        encode(py, 32, |out| self.0.encode_to_slice(out))
+    }
+
+    /// Compute the initial temporary contact key derived from this key.
+    fn initial_temporary_contact_key(&self) -> TemporaryContactKey {
+        TemporaryContactKey(self.0.initial_temporary_contact_key())
+    }
+
+    /// Compute the first `count` temporary contact numbers derived from this
+    /// key, starting with `tcn_1`.
+    pub fn temporary_contact_numbers<'py>(
+        &self,
+        py: Python<'py>,
+        count: u16,
+    ) -> Vec<Bound<'py, PyBytes>> {
+        let rak = self.0.clone();
+        let tcns = py.allow_threads(move || {
+            let mut tcns = Vec::with_capacity(count as usize);
+            let mut tck = Some(rak.initial_temporary_contact_key());
+            while let Some(key) = tck.take() {
+                if tcns.len() == count as usize {
+                    break;
+                }
+                tcns.push(key.temporary_contact_number());
+                tck = key.ratchet();
+            }
+            tcns
+        });
+        tcns.into_iter().map(|tcn| tcn_bytes(tcn, py)).collect()
+    }
+
+    /// Create a signed report disclosing the temporary contact numbers with
+    /// indices `j_1` through `j_2`.
+    fn create_report(
+        &self,
+        memo_type: u8,
+        memo_data: &[u8],
+        j_1: u16,
+        j_2: u16,
+    ) -> PyResult<SignedReport> {
+        let memo_type = memo_type.try_into().map_err(tcn_error)?;
+        self.0
+            .create_report(memo_type, memo_data.to_vec(), j_1, j_2)
+            .map(SignedReport)
+            .map_err(tcn_error)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// A ratcheting key used to derive temporary contact numbers.
+#[pyclass(module = "tcn")]
+pub struct TemporaryContactKey(tcn::TemporaryContactKey);
+
+#[pymethods]
+impl TemporaryContactKey {
+    /// Import a temporary contact key from its 66-byte encoding.
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        tcn::TemporaryContactKey::decode_from_slice(bytes)
+            .map(TemporaryContactKey)
+            .map_err(tcn_error)
+    }
+
+    /// Export this temporary contact key as 66 bytes.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        encode(py, 66, |out| self.0.encode_to_slice(out))
+    }
+
+    /// The index of this key.
+    #[getter]
+    fn index(&self) -> u16 {
+        self.0.index()
+    }
+
+    /// Compute the temporary contact number derived from this key.
+    fn temporary_contact_number<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        tcn_bytes(self.0.temporary_contact_number(), py)
+    }
+
+    /// Compute the next temporary contact key, or `None` once the last index
+    /// has been reached.
+    fn ratchet(&self) -> Option<TemporaryContactKey> {
+        self.0.clone().ratchet().map(TemporaryContactKey)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// A report whose source integrity has been verified.
+///
+/// Reports are only obtained from `SignedReport.verify`, so that an unsigned
+/// report can never be mistaken for a verified one.
+#[pyclass(module = "tcn")]
+#[derive(Clone)]
+pub struct Report(tcn::Report);
+
+#[pymethods]
+impl Report {
+    /// Serialize this report.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let mut bytes = Vec::new();
+        self.0.write(&mut bytes).map_err(tcn_error)?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    /// The wire format version of this report.
+    #[getter]
+    fn version(&self) -> u8 {
+        self.0.version() as u8
+    }
+
+    /// The numeric cipher suite of this report.
+    #[getter]
+    fn suite(&self) -> u8 {
+        self.0.suite() as u8
+    }
+
+    /// The numeric memo type of this report.
+    #[getter]
+    fn memo_type(&self) -> u8 {
+        self.0.memo_type() as u8
+    }
+
+    /// The memo data of this report.
+    #[getter]
+    fn memo_data<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, self.0.memo_data())
+    }
+
+    /// Recompute the temporary contact numbers disclosed by this report.
+    fn temporary_contact_numbers<'py>(&self, py: Python<'py>) -> Vec<Bound<'py, PyBytes>> {
+        self.0
+            .temporary_contact_numbers()
+            .map(|tcn| tcn_bytes(tcn, py))
+            .collect()
+    }
+}
+
+/// A report together with its signature.
+#[pyclass(module = "tcn")]
+pub struct SignedReport(tcn::SignedReport);
+
+#[pymethods]
+impl SignedReport {
+    /// Parse a signed report from its serialization, without verifying it.
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        tcn::SignedReport::decode_from_slice(bytes)
+            .map(SignedReport)
+            .map_err(tcn_error)
+    }
+
+    /// Serialize this signed report.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let mut bytes = Vec::new();
+        self.0.write(&mut bytes).map_err(tcn_error)?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    /// Verify the source integrity of this report, raising `TcnError` if the
+    /// signature is invalid.
+    fn verify(&self) -> PyResult<Report> {
+        self.0.clone().verify().map(Report).map_err(tcn_error)
+    }
+}
+
+/// Expand every report into its temporary contact numbers and match them
+/// against a collection of observed TCNs, each 16 `bytes`.
+///
+/// Returns a list of `(report_index, tcn)` pairs, one for each observed TCN
+/// disclosed by a report, ordered by report and then by TCN index.
+#[pyfunction]
+pub fn expand_and_match<'py>(
+    py: Python<'py>,
+    reports: Vec<PyRef<'py, Report>>,
+    observed: &Bound<'py, PyAny>,
+) -> PyResult<Vec<(usize, Bound<'py, PyBytes>)>> {
+    let mut observed_tcns = HashSet::new();
+    for tcn in observed.try_iter()? {
+        let tcn = tcn?;
+        let bytes: [u8; 16] = tcn
+            .extract::<&[u8]>()?
+            .try_into()
+            .map_err(|_| TcnError::new_err("Observed TCNs must be 16 bytes"))?;
+        observed_tcns.insert(bytes);
+    }
+    let reports = reports.iter().map(|r| r.0.clone()).collect::<Vec<_>>();
+
+    let matches = py.allow_threads(move || {
+        reports
+            .iter()
+            .enumerate()
+            .flat_map(|(i, report)| {
+                report
+                    .temporary_contact_numbers()
+                    .filter(|tcn| observed_tcns.contains(&tcn.0))
+                    .map(move |tcn| (i, tcn))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+    });
+    Ok(matches
+        .into_iter()
+        .map(|(i, tcn)| (i, tcn_bytes(tcn, py)))
+        .collect())
+}
+
+/// The `tcn` Python module.
+#[pymodule]
+#[pyo3(name = "tcn")]
+pub fn tcn_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("TcnError", m.py().get_type::<TcnError>())?;
+    m.add("MEMO_TYPE_COEPI_V1", tcn::MemoType::CoEpiV1 as u8)?;
+    m.add("MEMO_TYPE_COVIDWATCH_V1", tcn::MemoType::CovidWatchV1 as u8)?;
+    m.add_class::<ReportAuthorizationKey>()?;
+    m.add_class::<TemporaryContactKey>()?;
+    m.add_class::<Report>()?;
+    m.add_class::<SignedReport>()?;
+    m.add_function(wrap_pyfunction!(expand_and_match, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use std::ffi::CString;
+
+use pyo3::{prelude::*, types::PyDict, wrap_pymodule};
+
+/// Run a Python script with the `tcn` module in scope.
+fn run(script: &str) {
+    Python::with_gil(|py| {
+        let globals = PyDict::new(py);
+        globals
+            .set_item("tcn", wrap_pymodule!(tcn_py::tcn_py)(py))
+            .unwrap();
+        let script = CString::new(script).unwrap();
+        if let Err(e) = py.run(&script, Some(&globals), None) {
+            e.display(py);
+            panic!("Python script failed");
+        }
+    })
+}
+
+#[test]
+fn keys_reports_and_matching() {
+    run(r#"
+rak = tcn.ReportAuthorizationKey()
+assert "<redacted>" in repr(rak)
+
+# The batch derivation agrees with the ratchet.
+tcns = rak.temporary_contact_numbers(100)
+tck = rak.initial_temporary_contact_key()
+for i in range(100):
+    assert tck.index == i + 1
+    assert tck.temporary_contact_number() == tcns[i]
+    tck = tcn.TemporaryContactKey.from_bytes(tck.to_bytes()).ratchet()
+
+restored = tcn.ReportAuthorizationKey.from_secret_bytes(rak.expose_secret_bytes())
+assert restored.temporary_contact_numbers(100) == tcns
+
+signed = rak.create_report(tcn.MEMO_TYPE_COEPI_V1, b"symptom data", 20, 90)
+report = tcn.SignedReport.from_bytes(signed.to_bytes()).verify()
+assert report.memo_type == tcn.MEMO_TYPE_COEPI_V1
+assert report.memo_data == b"symptom data"
+assert report.temporary_contact_numbers() == tcns[19:90]
+# Reports can only be obtained by verifying a signed report.
+assert not hasattr(tcn.Report, "from_bytes")
+
+# Match two reports against TCNs observed by another user.
+other = tcn.ReportAuthorizationKey()
+other_report = other.create_report(tcn.MEMO_TYPE_COVIDWATCH_V1, b"", 1, 10).verify()
+observed = {tcns[5], tcns[50], other.temporary_contact_numbers(3)[2], bytes(16)}
+matches = tcn.expand_and_match([report, other_report], observed)
+assert matches == [(0, tcns[50]), (1, other.temporary_contact_numbers(3)[2])]
+"#);
+}
+
+#[test]
+fn errors_are_raised_as_tcn_errors() {
+    run(r#"
+rak = tcn.ReportAuthorizationKey()
+signed = bytearray(rak.create_report(0, b"", 1, 2).to_bytes())
+signed[70] ^= 1
+
+def raises(f):
+    try:
+        f()
+    except tcn.TcnError as e:
+        assert isinstance(e, ValueError)
+        return str(e)
+    raise AssertionError("expected a TcnError")
+
+assert raises(lambda: tcn.SignedReport.from_bytes(bytes(signed)).verify()) == "Report verification failed"
+assert raises(lambda: rak.create_report(7, b"", 1, 2)) == "Unknown memo type 7"
+assert raises(lambda: tcn.ReportAuthorizationKey.from_secret_bytes(b"short")).startswith("Invalid encoded length")
+assert raises(lambda: tcn.expand_and_match([], [b"short"])) == "Observed TCNs must be 16 bytes"
+"#);
+}
@@ -0,0 +1,196 @@
+//! WebAssembly bindings for the [`tcn`] crate, for browser-based tools such as
+//! dashboards that check uploaded reports.
+//!
+//! Build the JavaScript package with
+//! ```text
+//! wasm-pack build tcn-wasm
+//! ```
+//! and run the headless browser tests with
+//! ```text
+//! wasm-pack test --headless --firefox tcn-wasm
+//! ```
+//!
+//! Errors from the underlying crate are thrown as JavaScript `Error`s carrying
+//! the error message.
+
+#![deny(missing_docs)]
+
+use std::convert::TryInto;
+
+use js_sys::{Function, Uint8Array};
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+/// A signed report, as uploaded by a user.
+#[wasm_bindgen]
+pub struct SignedReport(tcn::SignedReport);
+
+#[wasm_bindgen]
+impl SignedReport {
+    /// Parse a signed report from its binary serialization, without verifying
+    /// it.
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<SignedReport, JsError> {
+        Ok(SignedReport(tcn::SignedReport::decode_from_slice(bytes)?))
+    }
+
+    /// Parse a signed report from padded base64, without verifying it.
+    #[wasm_bindgen(js_name = fromBase64)]
+    pub fn from_base64(s: &str) -> Result<SignedReport, JsError> {
+        Ok(SignedReport(tcn::SignedReport::from_base64(s)?))
+    }
+
+    /// Serialize this signed report.
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, JsError> {
+        let mut bytes = Vec::new();
+        self.0.write(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Encode this signed report as padded base64.
+    #[wasm_bindgen(js_name = toBase64)]
+    pub fn to_base64(&self) -> Result<String, JsError> {
+        Ok(self.0.to_base64()?)
+    }
+
+    /// Verify the source integrity of this report, throwing if the signature
+    /// is invalid.
+    pub fn verify(&self) -> Result<Report, JsError> {
+        Ok(Report(self.0.clone().verify()?))
+    }
+}
+
+/// A report whose source integrity has been verified.
+#[wasm_bindgen]
+pub struct Report(tcn::Report);
+
+#[wasm_bindgen]
+impl Report {
+    /// The wire format version of this report.
+    #[wasm_bindgen(getter)]
+    pub fn version(&self) -> u8 {
+        self.0.version() as u8
+    }
+
+    /// The numeric cipher suite of this report.
+    #[wasm_bindgen(getter)]
+    pub fn suite(&self) -> u8 {
+        self.0.suite() as u8
+    }
+
+    /// The numeric memo type of this report.
+    #[wasm_bindgen(getter, js_name = memoType)]
+    pub fn memo_type(&self) -> u8 {
+        self.0.memo_type() as u8
+    }
+
+    /// The name of the memo type of this report, such as `"CoEpiV1"`.
+    #[wasm_bindgen(getter, js_name = memoTypeName)]
+    pub fn memo_type_name(&self) -> String {
+        format!("{:?}", self.0.memo_type())
+    }
+
+    /// The raw memo data of this report.
+    #[wasm_bindgen(getter, js_name = memoData)]
+    pub fn memo_data(&self) -> Vec<u8> {
+        self.0.memo_data().to_vec()
+    }
+
+    /// The memo data decoded as UTF-8 text, or `undefined` if it is not valid
+    /// UTF-8.
+    #[wasm_bindgen(getter, js_name = memoText)]
+    pub fn memo_text(&self) -> Option<String> {
+        std::str::from_utf8(self.0.memo_data())
+            .ok()
+            .map(String::from)
+    }
+
+    /// Recompute the temporary contact numbers disclosed by this report, as
+    /// lowercase hex strings.
+    #[wasm_bindgen(js_name = temporaryContactNumbers)]
+    pub fn temporary_contact_numbers(&self) -> Vec<String> {
+        self.0
+            .temporary_contact_numbers()
+            .map(|tcn| tcn.to_hex())
+            .collect()
+    }
+}
+
+/// A secret report authorization key, used to create reports.
+#[wasm_bindgen]
+pub struct ReportAuthorizationKey(tcn::ReportAuthorizationKey);
+
+#[wasm_bindgen]
+impl ReportAuthorizationKey {
+    /// Generate a new report authorization key.
+    ///
+    /// `rng` is called with a `Uint8Array` to fill with random bytes, so that
+    /// `bytes => crypto.getRandomValues(bytes)` can be passed directly.  It
+    /// must be a cryptographically secure generator.  An all-zero result, as
+    /// left by a callback that does not fill the array, is rejected.
+    pub fn generate(rng: &Function) -> Result<ReportAuthorizationKey, JsError> {
+        let array = Uint8Array::new_with_length(32);
+        rng.call1(&JsValue::NULL, &array)
+            .map_err(|_| JsError::new("The random number generator threw an exception"))?;
+        let mut seed = Zeroizing::new([0; 32]);
+        array.copy_to(&mut seed[..]);
+        array.fill(0, 0, 32);
+        if seed.iter().fold(0, |acc, &byte| acc | byte) == 0 {
+            return Err(JsError::new(
+                "The random number generator did not fill the array",
+            ));
+        }
+        Ok(ReportAuthorizationKey(
+            tcn::ReportAuthorizationKey::decode_from_slice(&seed[..])?,
+        ))
+    }
+
+    /// Import a report authorization key from its secret hex encoding.
+    #[wasm_bindgen(js_name = fromSecretHex)]
+    pub fn from_secret_hex(s: &str) -> Result<ReportAuthorizationKey, JsError> {
+        Ok(ReportAuthorizationKey(
+            tcn::ReportAuthorizationKey::from_secret_hex(s)?,
+        ))
+    }
+
+    /// Export the **secret** report authorization key as a hex string.
+    #[wasm_bindgen(js_name = exposeSecretHex)]
+    pub fn expose_secret_hex(&self) -> String {
+        self.0.expose_secret_hex()
+    }
+
+    /// Create a signed report disclosing the temporary contact numbers with
+    /// indices `j1` through `j2`.
+    #[wasm_bindgen(js_name = createReport)]
+    pub fn create_report(
+        &self,
+        memo_type: u8,
+        memo_data: &[u8],
+        j1: u16,
+        j2: u16,
+    ) -> Result<SignedReport, JsError> {
+        Ok(SignedReport(self.0.create_report(
+            memo_type.try_into()?,
+            memo_data.to_vec(),
+            j1,
+            j2,
+        )?))
+    }
+
+    /// Compute the first `count` temporary contact numbers derived from this
+    /// key, as lowercase hex strings.
+    #[wasm_bindgen(js_name = temporaryContactNumbers)]
+    pub fn temporary_contact_numbers(&self, count: u16) -> Vec<String> {
+        let mut tcns = Vec::with_capacity(count as usize);
+        let mut tck = Some(self.0.initial_temporary_contact_key());
+        while let Some(key) = tck.take() {
+            if tcns.len() == count as usize {
+                break;
+            }
+            tcns.push(key.temporary_contact_number().to_hex());
+            tck = key.ratchet();
+        }
+        tcns
+    }
+}
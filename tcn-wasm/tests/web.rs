@@ -0,0 +1,65 @@
+//! Run with wasm-pack test --headless --firefox tcn-wasm
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::Function;
+use tcn_wasm::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn crypto_rng() -> Function {
+    Function::new_with_args("bytes", "crypto.getRandomValues(bytes)")
+}
+
+#[wasm_bindgen_test]
+fn create_and_verify_report() {
+    let rak = ReportAuthorizationKey::generate(&crypto_rng()).unwrap();
+    let tcns = rak.temporary_contact_numbers(20);
+    assert_eq!(tcns.len(), 20);
+
+    let signed_report = rak.create_report(1, b"positive test", 5, 15).unwrap();
+
+    // Round-trip the report through its encodings, as a dashboard would.
+    let bytes = signed_report.to_bytes().unwrap();
+    let signed_report = SignedReport::from_bytes(&bytes).unwrap();
+    let base64 = signed_report.to_base64().unwrap();
+    let signed_report = SignedReport::from_base64(&base64).unwrap();
+
+    let report = signed_report.verify().unwrap();
+    assert_eq!(report.memo_type(), 1);
+    assert_eq!(report.memo_type_name(), "CovidWatchV1");
+    assert_eq!(report.memo_data(), b"positive test");
+    assert_eq!(report.memo_text().as_deref(), Some("positive test"));
+    assert_eq!(report.temporary_contact_numbers(), tcns[4..15].to_vec());
+
+    // The key survives a round trip through its secret encoding.
+    let restored = ReportAuthorizationKey::from_secret_hex(&rak.expose_secret_hex()).unwrap();
+    assert_eq!(restored.temporary_contact_numbers(20), tcns);
+}
+
+#[wasm_bindgen_test]
+fn rejects_invalid_reports_and_rngs() {
+    let rak = ReportAuthorizationKey::generate(&crypto_rng()).unwrap();
+    let mut bytes = rak
+        .create_report(0, &[0xff, 0xfe], 1, 2)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+    assert!(SignedReport::from_bytes(&bytes)
+        .unwrap()
+        .verify()
+        .unwrap()
+        .memo_text()
+        .is_none());
+
+    bytes[70] ^= 1;
+    assert!(SignedReport::from_bytes(&bytes).unwrap().verify().is_err());
+    assert!(SignedReport::from_bytes(&bytes[..100]).is_err());
+    assert!(rak.create_report(7, b"", 1, 2).is_err());
+
+    let failing = Function::new_with_args("bytes", "throw new Error('no entropy')");
+    assert!(ReportAuthorizationKey::generate(&failing).is_err());
+    // A callback that leaves the array untouched does not yield a zero key.
+    let no_op = Function::new_with_args("bytes", "");
+    assert!(ReportAuthorizationKey::generate(&no_op).is_err());
+}
@@ -0,0 +1,112 @@
+use std::collections::BTreeSet;
+use tcn::*;
+
+#[test]
+fn verified_report_round_trip() {
+    let mut authority = LocalHealthAuthority::new(rand::thread_rng());
+    let token = authority.issue_token(rand::thread_rng());
+    assert!(token.verify(&authority.verification_key_bytes()).is_ok());
+
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let signed_report = rak
+        .create_verified_report(MemoType::CovidWatchV1, &token, b"test result", 20, 90)
+        .unwrap();
+    let mut bytes = Vec::new();
+    signed_report.write(&mut bytes).unwrap();
+
+    let (parsed, _) = SignedReportRef::parse(&bytes).unwrap();
+    let report = authority.redeem(&parsed).unwrap();
+    assert!(authority.is_redeemed(token.id()));
+    assert_eq!(
+        VerificationToken::from_memo(report.memo_data()).unwrap(),
+        token
+    );
+    assert_eq!(&report.memo_data()[80..], b"test result");
+
+    // The token cannot be redeemed again, with this report or any other.
+    assert!(matches!(
+        authority.redeem(&parsed),
+        Err(Error::TokenAlreadyRedeemed)
+    ));
+    let other = ReportAuthorizationKey::new(rand::thread_rng())
+        .create_verified_report(MemoType::CovidWatchV1, &token, b"", 1, 10)
+        .unwrap();
+    assert!(matches!(
+        authority.redeem_signed_report(other),
+        Err(Error::TokenAlreadyRedeemed)
+    ));
+}
+
+#[test]
+fn invalid_tokens_are_not_redeemed() {
+    let mut authority = LocalHealthAuthority::new(rand::thread_rng());
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+
+    // A token from another health authority, or none at all.
+    let foreign = HealthAuthorityKey::new(rand::thread_rng()).issue_token(rand::thread_rng());
+    let unverified = [
+        rak.create_verified_report(MemoType::CovidWatchV1, &foreign, b"", 1, 10)
+            .unwrap(),
+        rak.create_report(MemoType::CovidWatchV1, vec![0; 79], 1, 10)
+            .unwrap(),
+    ];
+    for signed_report in unverified.iter() {
+        assert!(matches!(
+            authority.redeem_signed_report(signed_report.clone()),
+            Err(Error::InvalidVerificationToken)
+        ));
+    }
+    assert!(!authority.is_redeemed(foreign.id()));
+
+    // A forged report does not use up the token it carries.
+    let token = authority.issue_token(rand::thread_rng());
+    let mut bytes = Vec::new();
+    rak.create_verified_report(MemoType::CovidWatchV1, &token, b"", 1, 10)
+        .unwrap()
+        .write(&mut bytes)
+        .unwrap();
+    *bytes.last_mut().unwrap() ^= 1;
+    let (forged, _) = SignedReportRef::parse(&bytes).unwrap();
+    assert!(matches!(
+        authority.redeem(&forged),
+        Err(Error::ReportVerificationFailed)
+    ));
+    assert!(!authority.is_redeemed(token.id()));
+}
+
+#[test]
+fn tokens_with_external_ledger() {
+    let key = HealthAuthorityKey::new(rand::thread_rng());
+    let mut bytes = [0; 32];
+    assert_eq!(key.encode_to_slice(&mut bytes).unwrap(), 32);
+    let decoded = HealthAuthorityKey::decode_from_slice(&bytes).unwrap();
+    assert_eq!(
+        decoded.verification_key_bytes(),
+        key.verification_key_bytes()
+    );
+    assert!(!format!("{:?}", key).contains(&hex::encode(bytes)));
+
+    let token = decoded.issue_token(rand::thread_rng());
+    let mut token_bytes = [0; 80];
+    assert_eq!(token.encode_to_slice(&mut token_bytes).unwrap(), 80);
+    assert_eq!(
+        VerificationToken::decode_from_slice(&token_bytes).unwrap(),
+        token
+    );
+    assert!(matches!(
+        VerificationToken::decode_from_slice(&token_bytes[..79]),
+        Err(Error::InvalidLength(79))
+    ));
+
+    let mut ledger = BTreeSet::new();
+    let signed_report = ReportAuthorizationKey::new(rand::thread_rng())
+        .create_verified_report(MemoType::CoEpiV1, &token, b"", 1, 10)
+        .unwrap();
+    let vk = key.verification_key_bytes();
+    assert!(signed_report.clone().redeem_token(&vk, &mut ledger).is_ok());
+    assert!(ledger.contains(token.id()));
+    assert!(matches!(
+        signed_report.redeem_token(&vk, &mut ledger),
+        Err(Error::TokenAlreadyRedeemed)
+    ));
+}
@@ -190,8 +190,8 @@ fn basic_read_write_round_trip() {
 }
 
 fn tcns_recompute_and_compare(
-    rak: ReportAuthorizationKey,
-    tcns: &Vec<TemporaryContactNumber>,
+    rak: &ReportAuthorizationKey,
+    tcns: &[TemporaryContactNumber],
     j_1: u16,
     j_2: u16,
 ) {
@@ -236,21 +236,22 @@ fn report_creation_index_boundaries_check() {
     //Generate all possible tcns (for comparison)
     for _ in 0..u16::MAX {
         tcns.push(tck.temporary_contact_number());
-        if let Some(new_tck) = tck.ratchet() {
-            tck = new_tck;
-        }
+        tck = match tck.ratchet() {
+            Some(new_tck) => new_tck,
+            None => break,
+        };
     }
 
     println!("generated tcns count: {}", tcns.len());
 
-    tcns_recompute_and_compare(rak, &tcns, 0, 1);
-    tcns_recompute_and_compare(rak, &tcns, 1, 1);
-    tcns_recompute_and_compare(rak, &tcns, 1, 2);
-    tcns_recompute_and_compare(rak, &tcns, 1, 200);
-    tcns_recompute_and_compare(rak, &tcns, 20, 90);
-    tcns_recompute_and_compare(rak, &tcns, 20000, 30000);
-    tcns_recompute_and_compare(rak, &tcns, u16::MAX - 1, u16::MAX);
-    tcns_recompute_and_compare(rak, &tcns, u16::MAX, u16::MAX);
+    tcns_recompute_and_compare(&rak, &tcns, 0, 1);
+    tcns_recompute_and_compare(&rak, &tcns, 1, 1);
+    tcns_recompute_and_compare(&rak, &tcns, 1, 2);
+    tcns_recompute_and_compare(&rak, &tcns, 1, 200);
+    tcns_recompute_and_compare(&rak, &tcns, 20, 90);
+    tcns_recompute_and_compare(&rak, &tcns, 20000, 30000);
+    tcns_recompute_and_compare(&rak, &tcns, u16::MAX - 1, u16::MAX);
+    tcns_recompute_and_compare(&rak, &tcns, u16::MAX, u16::MAX);
 }
 
 #[test]
@@ -275,3 +276,155 @@ fn memo_data_too_long() {
         .verify()
         .expect("Valid reports should verify correctly");
 }
+
+#[test]
+fn borrowed_report_views() {
+    use std::io::Cursor;
+
+    // Serialize a batch of signed reports back-to-back, as a server would.
+    let mut batch = Vec::new();
+    let mut signed_reports = Vec::new();
+    for i in 1..10u16 {
+        let rak = ReportAuthorizationKey::new(rand::thread_rng());
+        let signed_report = rak
+            .create_report(MemoType::CovidWatchV1, vec![i as u8; i as usize], i, 10 * i)
+            .expect("Report creation can only fail if the memo data is too long");
+        signed_report
+            .write(&mut batch)
+            .expect("writing should succeed");
+        signed_reports.push(signed_report);
+    }
+
+    let views = SignedReportRef::parse_all(&batch)
+        .collect::<Result<Vec<_>, _>>()
+        .expect("parsing should succeed");
+    assert_eq!(views.len(), signed_reports.len());
+
+    for (view, signed_report) in views.iter().zip(signed_reports) {
+        let mut buf1 = Vec::new();
+        let mut buf2 = Vec::new();
+        signed_report
+            .write(Cursor::new(&mut buf1))
+            .expect("writing should succeed");
+        view.to_signed_report()
+            .write(Cursor::new(&mut buf2))
+            .expect("writing should succeed");
+        assert_eq!(buf1, buf2);
+
+        let report_view = view
+            .verify()
+            .expect("Valid reports should verify correctly");
+        let report = signed_report
+            .verify()
+            .expect("Valid reports should verify correctly");
+        assert_eq!(report_view.memo_data(), report.memo_data());
+        assert_eq!(
+            report_view.temporary_contact_numbers().collect::<Vec<_>>(),
+            report.temporary_contact_numbers().collect::<Vec<_>>()
+        );
+        assert_eq!(report_view.index_range(), report.index_range());
+        assert_eq!(
+            report_view.verification_key_bytes(),
+            &report.verification_key_bytes()
+        );
+    }
+
+    // Flipping a bit of the memo data breaks verification.
+    let mut tampered = batch.clone();
+    tampered[32 + 32 + 2 + 2 + 1 + 1] ^= 1;
+    let (view, _) = SignedReportRef::parse(&tampered).expect("parsing should succeed");
+    assert!(view.verify().is_err());
+
+    // A truncated batch yields an error for the final report.
+    let results = SignedReportRef::parse_all(&batch[..batch.len() - 1]).collect::<Vec<_>>();
+    assert_eq!(results.len(), 9);
+    assert!(results[..8].iter().all(Result::is_ok));
+    assert!(matches!(results[8], Err(Error::InvalidLength(_))));
+}
+
+#[test]
+fn slice_based_report_creation_and_round_trip() {
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+
+    // Create a report without allocating, as a no_std device would.
+    let mut buf = [0u8; 256];
+    assert!(matches!(
+        rak.create_report_into(MemoType::CoEpiV1, b"symptom data", 20, 90, &mut buf[..100]),
+        Err(Error::BufferTooSmall(146))
+    ));
+    assert!(matches!(
+        rak.create_report_into(MemoType::CoEpiV1, &[0; 256], 20, 90, &mut buf[..100]),
+        Err(Error::OversizeMemo(256))
+    ));
+    let signed_report = rak
+        .create_report_into(MemoType::CoEpiV1, b"symptom data", 20, 90, &mut buf)
+        .expect("buffer is large enough");
+    let report = signed_report
+        .verify()
+        .expect("Valid reports should verify correctly");
+    assert_eq!(report.memo_data(), b"symptom data");
+
+    // It matches the report produced through the allocating API.
+    let owned = rak
+        .create_report(MemoType::CoEpiV1, b"symptom data".to_vec(), 20, 90)
+        .expect("Report creation can only fail if the memo data is too long");
+    let mut buf1 = [0u8; 146];
+    let mut buf2 = [0u8; 146];
+    assert_eq!(owned.encode_to_slice(&mut buf1).unwrap(), 146);
+    assert_eq!(
+        signed_report
+            .to_signed_report()
+            .encode_to_slice(&mut buf2)
+            .unwrap(),
+        146
+    );
+    assert_eq!(buf1, buf2);
+    assert_eq!(
+        report.temporary_contact_numbers().collect::<Vec<_>>(),
+        owned
+            .verify()
+            .unwrap()
+            .temporary_contact_numbers()
+            .collect::<Vec<_>>()
+    );
+
+    // Keys round-trip through slices.
+    let mut rak_buf = [0u8; 32];
+    rak.encode_to_slice(&mut rak_buf).unwrap();
+    let rak2 = ReportAuthorizationKey::decode_from_slice(&rak_buf).unwrap();
+    let tck = rak2.initial_temporary_contact_key();
+    let mut tck_buf = [0u8; 66];
+    assert_eq!(tck.encode_to_slice(&mut tck_buf).unwrap(), 66);
+    assert_eq!(
+        TemporaryContactKey::decode_from_slice(&tck_buf).unwrap(),
+        tck
+    );
+    assert_eq!(
+        tck.temporary_contact_number(),
+        rak.initial_temporary_contact_key()
+            .temporary_contact_number()
+    );
+    assert!(matches!(
+        TemporaryContactKey::decode_from_slice(&tck_buf[..65]),
+        Err(Error::InvalidLength(65))
+    ));
+}
+
+#[test]
+fn secret_material_is_redacted_and_zeroized() {
+    fn assert_zeroize_on_drop<T: zeroize::ZeroizeOnDrop>() {}
+    assert_zeroize_on_drop::<ReportAuthorizationKey>();
+    assert_zeroize_on_drop::<TemporaryContactKey>();
+
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let mut rak_bytes = [0u8; 32];
+    rak.encode_to_slice(&mut rak_bytes).unwrap();
+    assert!(!format!("{:?}", rak).contains(&hex::encode(rak_bytes)));
+
+    let tck = rak.initial_temporary_contact_key();
+    let mut tck_bytes = [0u8; 66];
+    tck.encode_to_slice(&mut tck_bytes).unwrap();
+    let debug = format!("{:?}", tck);
+    assert!(debug.contains("index: 1"));
+    assert!(!debug.contains(&hex::encode(&tck_bytes[34..])));
+}
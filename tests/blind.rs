@@ -0,0 +1,136 @@
+#![cfg(feature = "blind-auth")]
+
+use std::collections::BTreeSet;
+use tcn::*;
+
+fn authorize(
+    authority: &BlindAuthorityKey,
+    rak: &ReportAuthorizationKey,
+) -> (BlindRequest, BlindAuthorization) {
+    let (request, blinding) = rak.blind_authorization_request(rand::thread_rng());
+    let response = authority.evaluate(&request, rand::thread_rng()).unwrap();
+    let authorization = blinding
+        .finish(&response, &authority.public_key_bytes())
+        .unwrap();
+    (request, authorization)
+}
+
+#[test]
+fn blind_authorization_round_trip() {
+    let authority = BlindAuthorityKey::new(rand::thread_rng());
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let (request, authorization) = authorize(&authority, &rak);
+
+    let signed_report = rak
+        .create_blind_authorized_report(
+            MemoType::CovidWatchV1,
+            &authorization,
+            b"test result",
+            20,
+            90,
+        )
+        .unwrap();
+    let mut bytes = Vec::new();
+    signed_report.write(&mut bytes).unwrap();
+    let (parsed, _) = SignedReportRef::parse(&bytes).unwrap();
+
+    // The health authority never saw the report verification key or the
+    // authorization, so it cannot link the report to the request.
+    let report = parsed.verify().unwrap();
+    let mut request_bytes = [0; 32];
+    request.encode_to_slice(&mut request_bytes).unwrap();
+    assert!(!bytes.windows(32).any(|window| window == request_bytes));
+    assert_eq!(&report.memo_data()[32..], b"test result");
+
+    let mut ledger = BTreeSet::new();
+    assert!(authority.redeem(&parsed, &mut ledger).is_ok());
+    assert_eq!(ledger.len(), 1);
+    assert!(matches!(
+        authority.redeem(&parsed, &mut ledger),
+        Err(Error::TokenAlreadyRedeemed)
+    ));
+
+    // Requests for the same key unblind to the same authorization.
+    assert_eq!(authorize(&authority, &rak).1, authorization);
+}
+
+#[test]
+fn authorizations_are_bound_to_keys() {
+    let authority = BlindAuthorityKey::new(rand::thread_rng());
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let (_, authorization) = authorize(&authority, &rak);
+    let mut ledger = BTreeSet::new();
+
+    // Another key, another authority, or no authorization at all.
+    let other_rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let other_authority = BlindAuthorityKey::new(rand::thread_rng());
+    let (_, foreign) = authorize(&other_authority, &rak);
+    let unauthorized = [
+        other_rak
+            .create_blind_authorized_report(MemoType::CoEpiV1, &authorization, b"", 1, 10)
+            .unwrap(),
+        rak.create_blind_authorized_report(MemoType::CoEpiV1, &foreign, b"", 1, 10)
+            .unwrap(),
+        rak.create_report(MemoType::CoEpiV1, vec![0; 31], 1, 10)
+            .unwrap(),
+    ];
+    for signed_report in unauthorized.iter() {
+        assert!(matches!(
+            authority.redeem_signed_report(signed_report.clone(), &mut ledger),
+            Err(Error::BlindAuthorizationFailed)
+        ));
+    }
+    assert!(ledger.is_empty());
+}
+
+#[test]
+fn responses_are_checked() {
+    let authority = BlindAuthorityKey::new(rand::thread_rng());
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let (request, blinding) = rak.blind_authorization_request(rand::thread_rng());
+
+    // A response from a key other than the published one, which could be
+    // used to tag the requester, is rejected.
+    let tagging = BlindAuthorityKey::new(rand::thread_rng());
+    let response = tagging.evaluate(&request, rand::thread_rng()).unwrap();
+    assert!(matches!(
+        blinding.finish(&response, &authority.public_key_bytes()),
+        Err(Error::BlindAuthorizationFailed)
+    ));
+
+    let (request, blinding) = rak.blind_authorization_request(rand::thread_rng());
+    let response = authority.evaluate(&request, rand::thread_rng()).unwrap();
+    let mut bytes = [0; 96];
+    assert_eq!(response.encode_to_slice(&mut bytes).unwrap(), 96);
+    bytes[40] ^= 1;
+    let tampered = BlindResponse::decode_from_slice(&bytes).unwrap();
+    assert!(matches!(
+        blinding.finish(&tampered, &authority.public_key_bytes()),
+        Err(Error::BlindAuthorizationFailed)
+    ));
+
+    // Requests must encode a valid point.
+    let invalid = BlindRequest::decode_from_slice(&[0xff; 32]).unwrap();
+    assert!(matches!(
+        authority.evaluate(&invalid, rand::thread_rng()),
+        Err(Error::BlindAuthorizationFailed)
+    ));
+}
+
+#[test]
+fn blind_authority_key_encoding() {
+    let authority = BlindAuthorityKey::new(rand::thread_rng());
+    let mut bytes = [0; 32];
+    assert_eq!(authority.encode_to_slice(&mut bytes).unwrap(), 32);
+    let decoded = BlindAuthorityKey::decode_from_slice(&bytes).unwrap();
+    assert_eq!(decoded.public_key_bytes(), authority.public_key_bytes());
+    assert!(!format!("{:?}", authority).contains(&hex::encode(bytes)));
+    assert!(matches!(
+        BlindAuthorityKey::decode_from_slice(&[0xff; 32]),
+        Err(Error::BlindAuthorizationFailed)
+    ));
+    assert!(matches!(
+        BlindAuthorityKey::decode_from_slice(&bytes[..31]),
+        Err(Error::InvalidLength(31))
+    ));
+}
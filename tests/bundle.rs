@@ -0,0 +1,130 @@
+use tcn::*;
+
+fn signed_reports(count: usize) -> Vec<SignedReport> {
+    (0..count)
+        .map(|i| {
+            ReportAuthorizationKey::new(rand::thread_rng())
+                .create_report(MemoType::CoEpiV1, vec![i as u8; i], 1, 10 + i as u16)
+                .expect("Report creation can only fail if the memo data is too long")
+        })
+        .collect()
+}
+
+#[test]
+fn bundle_round_trip() {
+    let publisher = PublisherKey::new(rand::thread_rng());
+    let signed_reports = signed_reports(5);
+
+    let mut uploaded = Vec::new();
+    for signed_report in &signed_reports {
+        signed_report.write(&mut uploaded).unwrap();
+    }
+    let mut builder = ReportBundleBuilder::new(42);
+    for signed_report in SignedReportRef::parse_all(&uploaded) {
+        builder.push(&signed_report.unwrap()).unwrap();
+    }
+    assert_eq!(builder.len(), 5);
+    let bundle = builder.finish(&publisher);
+    // Each report loses its 64-byte signature.
+    assert_eq!(bundle.len(), 8 + uploaded.len() - 5 * 64 + 64);
+
+    let bundle = ReportBundleRef::verify(&bundle, &publisher.verification_key_bytes()).unwrap();
+    assert_eq!(bundle.sequence(), 42);
+    let reports = bundle.reports().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(reports.len(), 5);
+    for (report, signed_report) in reports.iter().zip(signed_reports) {
+        let expected = signed_report.verify().unwrap();
+        assert_eq!(report.memo_data(), expected.memo_data());
+        assert!(report
+            .temporary_contact_numbers()
+            .eq(expected.temporary_contact_numbers()));
+    }
+}
+
+#[test]
+fn only_verified_reports_are_stripped() {
+    let mut builder = ReportBundleBuilder::new(1).with_policy(ReportPolicy::default());
+
+    let mut bytes = Vec::new();
+    signed_reports(1)[0].write(&mut bytes).unwrap();
+    *bytes.last_mut().unwrap() ^= 1;
+    let (forged, _) = SignedReportRef::parse(&bytes).unwrap();
+    assert!(matches!(
+        builder.push(&forged),
+        Err(Error::ReportVerificationFailed)
+    ));
+
+    let oversized = ReportAuthorizationKey::new(rand::thread_rng())
+        .create_report(MemoType::CoEpiV1, Vec::new(), 1, u16::MAX)
+        .unwrap();
+    assert!(matches!(
+        builder.push_signed_report(oversized),
+        Err(Error::ReportRangeTooLarge(u16::MAX))
+    ));
+    assert!(builder.is_empty());
+
+    // The bare stream holds only the reports that were accepted.
+    for signed_report in signed_reports(3) {
+        builder.push_signed_report(signed_report).unwrap();
+    }
+    let stream = builder.into_report_stream();
+    let reports = ReportRef::parse_all(&stream)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(reports.len(), 3);
+    assert_eq!(stream.len(), 3 * 70 + 1 + 2);
+}
+
+#[test]
+fn bundle_signature_is_checked() {
+    let publisher = PublisherKey::new(rand::thread_rng());
+    let vk = publisher.verification_key_bytes();
+    let mut builder = ReportBundleBuilder::new(7);
+    for signed_report in signed_reports(2) {
+        builder.push_signed_report(signed_report).unwrap();
+    }
+    let bundle = builder.finish(&publisher);
+    assert!(ReportBundleRef::verify(&bundle, &vk).is_ok());
+
+    // Any change to the sequence number, the reports or the signature, or a
+    // different publisher, fails verification.
+    for i in [0, 8, 8 + 70, bundle.len() - 1].iter() {
+        let mut tampered = bundle.clone();
+        tampered[*i] ^= 1;
+        assert!(matches!(
+            ReportBundleRef::verify(&tampered, &vk),
+            Err(Error::BundleVerificationFailed)
+        ));
+    }
+    let other = PublisherKey::new(rand::thread_rng()).verification_key_bytes();
+    assert!(matches!(
+        ReportBundleRef::verify(&bundle, &other),
+        Err(Error::BundleVerificationFailed)
+    ));
+    assert!(matches!(
+        ReportBundleRef::verify(&bundle[..71], &vk),
+        Err(Error::InvalidLength(71))
+    ));
+
+    // An empty bundle is valid.
+    let empty = ReportBundleBuilder::new(8).finish(&publisher);
+    let empty = ReportBundleRef::verify(&empty, &vk).unwrap();
+    assert_eq!(empty.reports().count(), 0);
+}
+
+#[test]
+fn publisher_key_encoding() {
+    let publisher = PublisherKey::new(rand::thread_rng());
+    let mut bytes = [0; 32];
+    assert_eq!(publisher.encode_to_slice(&mut bytes).unwrap(), 32);
+    let decoded = PublisherKey::decode_from_slice(&bytes).unwrap();
+    assert_eq!(
+        decoded.verification_key_bytes(),
+        publisher.verification_key_bytes()
+    );
+    assert!(!format!("{:?}", publisher).contains(&hex::encode(bytes)));
+    assert!(matches!(
+        PublisherKey::decode_from_slice(&bytes[..31]),
+        Err(Error::InvalidLength(31))
+    ));
+}
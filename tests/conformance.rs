@@ -0,0 +1,170 @@
+//! Checks the implementation against the conformance vectors in
+//! `tests/vectors.json`, which other implementations can use to prove
+//! conformance.  Regenerate the file with
+//! `cargo run -p tcn-cli -- vectors --json > tests/vectors.json`.
+
+use serde_json::Value;
+use std::convert::TryFrom;
+use tcn::*;
+
+fn vectors() -> Value {
+    serde_json::from_str(include_str!("vectors.json")).expect("vectors.json should parse")
+}
+
+fn bytes(value: &Value) -> Vec<u8> {
+    hex::decode(value.as_str().expect("expected a hex string")).expect("expected valid hex")
+}
+
+fn number(value: &Value) -> u16 {
+    value.as_u64().expect("expected a number") as u16
+}
+
+fn tck_bytes(tck: &TemporaryContactKey) -> [u8; 66] {
+    let mut bytes = [0; 66];
+    tck.encode_to_slice(&mut bytes).unwrap();
+    bytes
+}
+
+/// The name of an error's variant, as used in the vectors.
+fn error_name(e: &Error) -> String {
+    format!("{:?}", e).split('(').next().unwrap().to_string()
+}
+
+#[test]
+fn key_derivation_vectors() {
+    let vectors = vectors();
+    let rak = ReportAuthorizationKey::decode_from_slice(&bytes(&vectors["rak"])).unwrap();
+    let mut tck = rak.initial_temporary_contact_key();
+    assert_eq!(tck_bytes(&tck)[2..34], bytes(&vectors["rvk"])[..]);
+
+    let chain = vectors["tck_chain"].as_array().unwrap();
+    let spot = vectors["tcns"].as_array().unwrap();
+    let (mut chain, mut spot) = (chain.iter().peekable(), spot.iter().peekable());
+    loop {
+        if let Some(vector) = chain.next_if(|v| number(&v["index"]) == tck.index()) {
+            assert_eq!(tck_bytes(&tck)[34..], bytes(&vector["tck"])[..]);
+            assert_eq!(
+                tck.temporary_contact_number().0[..],
+                bytes(&vector["tcn"])[..]
+            );
+        }
+        if let Some(vector) = spot.next_if(|v| number(&v["index"]) == tck.index()) {
+            assert_eq!(
+                tck.temporary_contact_number().0[..],
+                bytes(&vector["tcn"])[..]
+            );
+        }
+        match tck.ratchet() {
+            Some(next) => tck = next,
+            None => break,
+        }
+    }
+    assert!(chain.next().is_none() && spot.next().is_none());
+}
+
+#[test]
+fn report_vectors() {
+    let vectors = vectors();
+    let rak = ReportAuthorizationKey::decode_from_slice(&bytes(&vectors["rak"])).unwrap();
+
+    let mut memo_types = Vec::new();
+    let mut versions = Vec::new();
+    let mut suites = Vec::new();
+    for vector in vectors["reports"].as_array().unwrap() {
+        let version = match number(&vector["version"]) {
+            0 => ReportVersion::V0,
+            1 => ReportVersion::V1,
+            2 => ReportVersion::V2,
+            3 => ReportVersion::V3,
+            v => panic!("unknown report version {}", v),
+        };
+        versions.push(version);
+        let suite = CipherSuiteId::try_from(number(&vector["suite"]) as u8).unwrap();
+        suites.push(suite);
+        let memo_type = MemoType::try_from(number(&vector["memo_type"]) as u8).unwrap();
+        memo_types.push(memo_type);
+        let signed_bytes = bytes(&vector["signed_report"]);
+        assert_eq!(
+            signed_bytes,
+            [bytes(&vector["report"]), bytes(&vector["signature"])].concat()
+        );
+
+        // Signatures are deterministic, so creation reproduces the vector.
+        let (memo_data, j_1, j_2) = (
+            bytes(&vector["memo_data"]),
+            number(&vector["j_1"]),
+            number(&vector["j_2"]),
+        );
+        let created = match suite {
+            CipherSuiteId::Sha256Ed25519 => {
+                rak.create_versioned_report(version, memo_type, memo_data, j_1, j_2)
+            }
+            CipherSuiteId::Blake3Ed25519 => rak.create_versioned_suite_report::<Blake3Ed25519>(
+                version, memo_type, memo_data, j_1, j_2,
+            ),
+        };
+        let mut created_bytes = Vec::new();
+        created.unwrap().write(&mut created_bytes).unwrap();
+        assert_eq!(created_bytes, signed_bytes);
+
+        let report = SignedReport::decode_from_slice(&signed_bytes)
+            .unwrap()
+            .verify()
+            .unwrap();
+        assert_eq!(report.version(), version);
+        assert_eq!(report.suite(), suite);
+        assert_eq!(report.memo_type(), memo_type);
+        assert_eq!(report.memo_data(), &bytes(&vector["memo_data"])[..]);
+        assert_eq!(
+            report.index_range(),
+            number(&vector["j_1"])..=number(&vector["j_2"])
+        );
+        let tcns = vector["tcns"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tcn| TemporaryContactNumber::from_hex(tcn.as_str().unwrap()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(report.temporary_contact_numbers().collect::<Vec<_>>(), tcns);
+
+        let unsigned = Report::decode_from_slice(&bytes(&vector["report"])).unwrap();
+        assert_eq!(
+            unsigned.to_hex().unwrap(),
+            vector["report"].as_str().unwrap()
+        );
+    }
+    // Every memo type is covered.
+    assert!(memo_types.contains(&MemoType::CoEpiV1));
+    assert!(memo_types.contains(&MemoType::CovidWatchV1));
+    // As is every report version and cipher suite.
+    assert!(versions.contains(&ReportVersion::V0));
+    assert!(versions.contains(&ReportVersion::V1));
+    assert!(versions.contains(&ReportVersion::V2));
+    assert!(versions.contains(&ReportVersion::V3));
+    assert!(suites.contains(&CipherSuiteId::Sha256Ed25519));
+    assert!(suites.contains(&CipherSuiteId::Blake3Ed25519));
+}
+
+#[test]
+fn invalid_report_vectors() {
+    for vector in vectors()["invalid_reports"].as_array().unwrap() {
+        let signed_bytes = bytes(&vector["signed_report"]);
+        let expected = vector["error"].as_str().unwrap();
+
+        let error = match SignedReport::decode_from_slice(&signed_bytes) {
+            Ok(signed_report) => signed_report.verify().unwrap_err(),
+            Err(e) => e,
+        };
+        assert_eq!(error_name(&error), expected, "{}", vector["description"]);
+
+        let error = match SignedReportRef::parse(&signed_bytes) {
+            Ok((signed_report, _)) => signed_report.verify().unwrap_err(),
+            Err(e) => e,
+        };
+        assert_eq!(error_name(&error), expected, "{}", vector["description"]);
+
+        assert!(SignedReport::read(&signed_bytes[..])
+            .and_then(SignedReport::verify)
+            .is_err());
+    }
+}
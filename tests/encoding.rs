@@ -0,0 +1,104 @@
+use tcn::*;
+
+#[test]
+fn text_encoding_round_trips() {
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+
+    let tcn = rak
+        .initial_temporary_contact_key()
+        .temporary_contact_number();
+    assert_eq!(tcn.to_string().len(), 32);
+    assert_eq!(
+        tcn.to_string().parse::<TemporaryContactNumber>().unwrap(),
+        tcn
+    );
+    assert_eq!(
+        TemporaryContactNumber::from_hex(&tcn.to_hex()).unwrap(),
+        tcn
+    );
+    assert_eq!(
+        TemporaryContactNumber::from_base64(&tcn.to_base64()).unwrap(),
+        tcn
+    );
+
+    let signed_report = rak
+        .create_report(MemoType::CoEpiV1, b"symptom data".to_vec(), 20, 90)
+        .expect("Report creation can only fail if the memo data is too long");
+
+    let encoded = signed_report.to_string();
+    assert_eq!(encoded, signed_report.to_base64().unwrap());
+    let decoded = encoded.parse::<SignedReport>().unwrap();
+    assert_eq!(decoded.to_string(), encoded);
+    let hex = signed_report.to_hex().unwrap();
+    assert_eq!(SignedReport::from_hex(&hex).unwrap().to_hex().unwrap(), hex);
+
+    let report = decoded
+        .verify()
+        .expect("Valid reports should verify correctly");
+    let encoded = report.to_string();
+    assert_eq!(encoded.parse::<Report>().unwrap().to_string(), encoded);
+    let hex = report.to_hex().unwrap();
+    assert_eq!(Report::from_hex(&hex).unwrap().to_hex().unwrap(), hex);
+
+    let exported = rak.expose_secret_hex();
+    assert_eq!(
+        ReportAuthorizationKey::from_secret_hex(&exported)
+            .unwrap()
+            .expose_secret_base64(),
+        rak.expose_secret_base64()
+    );
+    assert_eq!(
+        ReportAuthorizationKey::from_secret_base64(&rak.expose_secret_base64())
+            .unwrap()
+            .expose_secret_hex(),
+        exported
+    );
+    assert!(!format!("{:?}", rak).contains(&exported));
+}
+
+#[test]
+fn text_decoding_is_strict() {
+    // Wrong lengths.
+    assert!(matches!(
+        TemporaryContactNumber::from_hex("00112233"),
+        Err(Error::InvalidLength(4))
+    ));
+    assert!(matches!(
+        ReportAuthorizationKey::from_secret_hex(&"00".repeat(33)),
+        Err(Error::InvalidLength(33))
+    ));
+
+    // Bad alphabets and padding.
+    assert!(matches!(
+        TemporaryContactNumber::from_hex(&"zz".repeat(16)),
+        Err(Error::InvalidHex)
+    ));
+    assert!(matches!(
+        TemporaryContactNumber::from_hex(&"0".repeat(31)),
+        Err(Error::InvalidHex)
+    ));
+    assert!(matches!(
+        TemporaryContactNumber::from_base64("AAAAAAAAAAAAAAAAAAAAAA"),
+        Err(Error::InvalidBase64)
+    ));
+    assert!(matches!(
+        TemporaryContactNumber::from_base64("AAAAAAAAAAAAAAAAAAAAA-=="),
+        Err(Error::InvalidBase64)
+    ));
+
+    // Truncated and padded reports.
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let hex = rak
+        .create_report(MemoType::CovidWatchV1, b"test data".to_vec(), 1, 10)
+        .unwrap()
+        .to_hex()
+        .unwrap();
+    assert!(matches!(
+        SignedReport::from_hex(&hex[..hex.len() - 2]),
+        Err(Error::InvalidLength(_))
+    ));
+    assert!(matches!(
+        SignedReport::from_hex(&format!("{}00", hex)),
+        Err(Error::InvalidLength(_))
+    ));
+}
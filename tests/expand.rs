@@ -0,0 +1,104 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use tcn::*;
+
+fn reports(count: usize) -> Vec<Report> {
+    (0..count)
+        .map(|_| {
+            ReportAuthorizationKey::new(rand::thread_rng())
+                .create_report(MemoType::CoEpiV1, Vec::new(), 1, 50)
+                .unwrap()
+                .verify()
+                .unwrap()
+        })
+        .collect()
+}
+
+fn in_report_order(reports: &[Report]) -> Vec<TemporaryContactNumber> {
+    reports
+        .iter()
+        .flat_map(|report| report.temporary_contact_numbers())
+        .collect()
+}
+
+#[test]
+fn merged_expansion_is_deduplicated() {
+    let reports = reports(4);
+    let mut expander = ReportExpander::new();
+    for report in &reports {
+        expander.push_report(report);
+    }
+    // A report uploaded twice contributes its TCNs once.
+    let mut bytes = Vec::new();
+    reports[0].write(&mut bytes).unwrap();
+    expander.push_report_ref(&ReportRef::parse(&bytes).unwrap().0);
+    assert_eq!(expander.len(), 5 * 50);
+
+    let sorted = expander.clone().finish_sorted();
+    assert!(sorted.is_sorted());
+    assert_eq!(sorted.len(), 4 * 50);
+    assert!(sorted.as_slice().windows(2).all(|pair| pair[0] < pair[1]));
+
+    let shuffled = expander.finish_shuffled(rand::thread_rng());
+    assert!(!shuffled.is_sorted());
+    let mut resorted = shuffled.as_slice().to_vec();
+    resorted.sort();
+    assert_eq!(resorted, sorted.as_slice());
+    for tcn in in_report_order(&reports) {
+        assert!(sorted.contains(&tcn));
+        assert!(shuffled.contains(&tcn));
+    }
+    assert!(!sorted.contains(&TemporaryContactNumber([0; 16])));
+}
+
+#[test]
+fn shuffled_expansion_decorrelates_reports() {
+    let reports = reports(4);
+    let ordered = in_report_order(&reports);
+    let shuffle = |seed| {
+        let mut expander = ReportExpander::new();
+        for report in &reports {
+            expander.push_report(report);
+        }
+        expander.finish_shuffled(ChaCha20Rng::seed_from_u64(seed))
+    };
+
+    // The shuffle depends only on the RNG...
+    assert_eq!(shuffle(1), shuffle(1));
+    assert_ne!(shuffle(1), shuffle(2));
+    // ...and TCNs adjacent in a report are rarely adjacent in the list.
+    let list = shuffle(3);
+    let position = |tcn| list.as_slice().iter().position(|t| *t == tcn).unwrap();
+    let adjacent = ordered
+        .windows(2)
+        .filter(|pair| (position(pair[0]) as isize - position(pair[1]) as isize).abs() == 1)
+        .count();
+    assert!(adjacent < 20, "{} adjacent pairs", adjacent);
+}
+
+#[test]
+fn tcn_list_encoding() {
+    let mut expander = ReportExpander::new();
+    for report in reports(2).iter() {
+        expander.push_report(report);
+    }
+    let shuffled = expander.clone().finish_shuffled(rand::thread_rng());
+    let sorted = expander.finish_sorted();
+    for list in [sorted, shuffled].iter() {
+        let mut bytes = vec![0; list.encoded_len()];
+        assert_eq!(list.encode_to_slice(&mut bytes).unwrap(), 100 * 16);
+        let decoded = TcnList::decode_from_slice(&bytes).unwrap();
+        assert_eq!(&decoded, list);
+        assert!(matches!(
+            list.encode_to_slice(&mut bytes[1..]),
+            Err(Error::BufferTooSmall(1600))
+        ));
+    }
+    assert!(matches!(
+        TcnList::decode_from_slice(&[0; 17]),
+        Err(Error::InvalidLength(17))
+    ));
+    let empty = ReportExpander::new().finish_shuffled(rand::thread_rng());
+    assert!(empty.is_empty());
+    assert_eq!(empty.encoded_len(), 0);
+}
@@ -0,0 +1,29 @@
+//! Inputs found by the fuzz targets in `fuzz/`, kept as regression tests.
+
+use tcn::*;
+
+/// `fuzz/report_read`: a report claiming a 118-byte memo with no memo bytes
+/// was accepted by `Report::read` with an empty memo.
+#[test]
+fn report_read_rejects_truncated_memo() {
+    let input = hex::decode(
+        "0affffff7676767676767676767676767676767676767676626262626276767676767f76\
+         14000030303076303030303030323438373739313132323434303734373233340076",
+    )
+    .unwrap();
+    assert!(Report::read(&input[..]).is_err());
+    assert!(ReportRef::parse(&input).is_err());
+
+    // The same bug, reached from a valid report.
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let mut bytes = Vec::new();
+    rak.create_report(MemoType::CoEpiV1, b"symptom data".to_vec(), 2, 10)
+        .unwrap()
+        .write(&mut bytes)
+        .unwrap();
+    let report = &bytes[..bytes.len() - 64];
+    for len in 70..report.len() {
+        assert!(Report::read(&report[..len]).is_err());
+    }
+    assert!(Report::read(report).is_ok());
+}
@@ -0,0 +1,151 @@
+use tcn::*;
+
+fn report(memo_type: MemoType, memo_data: &[u8], j_1: u16, j_2: u16) -> SignedReport {
+    ReportAuthorizationKey::new(rand::thread_rng())
+        .create_report(memo_type, memo_data.to_vec(), j_1, j_2)
+        .expect("Report creation can only fail if the memo data is too long")
+}
+
+fn check(policy: &ReportPolicy, signed_report: SignedReport) -> Result<(), Error> {
+    let mut bytes = Vec::new();
+    signed_report.write(&mut bytes).unwrap();
+    let (borrowed, _) = SignedReportRef::parse(&bytes).unwrap();
+
+    // The owned and borrowed reports are checked identically.
+    let borrowed = borrowed.verify_with_policy(policy).map(|_| ());
+    let owned = signed_report.verify_with_policy(policy).map(|_| ());
+    assert_eq!(format!("{:?}", borrowed), format!("{:?}", owned));
+    owned
+}
+
+#[test]
+fn index_range_limits() {
+    let policy = ReportPolicy::new(100).allow_memo_type(MemoType::CoEpiV1, MemoPolicy::default());
+
+    assert!(check(&policy, report(MemoType::CoEpiV1, b"", 1, 1)).is_ok());
+    assert!(check(&policy, report(MemoType::CoEpiV1, b"", 20, 119)).is_ok());
+    assert!(matches!(
+        check(&policy, report(MemoType::CoEpiV1, b"", 20, 120)),
+        Err(Error::ReportRangeTooLarge(101))
+    ));
+    assert!(matches!(
+        check(&policy, report(MemoType::CoEpiV1, b"", 1, u16::MAX)),
+        Err(Error::ReportRangeTooLarge(u16::MAX))
+    ));
+    assert!(matches!(
+        check(&policy, report(MemoType::CoEpiV1, b"", 20, 19)),
+        Err(Error::ReportRangeInverted(20, 19))
+    ));
+
+    // Even a policy with no span limit rejects an inverted range.
+    let unlimited =
+        ReportPolicy::new(u16::MAX).allow_memo_type(MemoType::CoEpiV1, MemoPolicy::default());
+    assert!(check(&unlimited, report(MemoType::CoEpiV1, b"", 1, u16::MAX)).is_ok());
+    assert!(matches!(
+        check(&unlimited, report(MemoType::CoEpiV1, b"", u16::MAX, 1)),
+        Err(Error::ReportRangeInverted(u16::MAX, 1))
+    ));
+}
+
+#[test]
+fn memo_limits() {
+    fn is_test_result(memo_data: &[u8]) -> bool {
+        memo_data == b"positive" || memo_data == b"negative"
+    }
+
+    let policy = ReportPolicy::new(100)
+        .allow_memo_type(
+            MemoType::CoEpiV1,
+            MemoPolicy {
+                max_len: 16,
+                schema: MemoSchema::Utf8,
+            },
+        )
+        .allow_memo_type(
+            MemoType::CovidWatchV1,
+            MemoPolicy {
+                max_len: 255,
+                schema: MemoSchema::Custom(is_test_result),
+            },
+        );
+    assert_eq!(policy.memo_policy(MemoType::CoEpiV1).unwrap().max_len, 16);
+
+    assert!(check(&policy, report(MemoType::CoEpiV1, b"symptom data", 1, 2)).is_ok());
+    assert!(matches!(
+        check(&policy, report(MemoType::CoEpiV1, &[b'a'; 17], 1, 2)),
+        Err(Error::MemoTooLong(17))
+    ));
+    assert!(matches!(
+        check(&policy, report(MemoType::CoEpiV1, b"\xff", 1, 2)),
+        Err(Error::InvalidMemo(0))
+    ));
+    assert!(check(&policy, report(MemoType::CovidWatchV1, b"positive", 1, 2)).is_ok());
+    assert!(matches!(
+        check(&policy, report(MemoType::CovidWatchV1, b"maybe", 1, 2)),
+        Err(Error::InvalidMemo(1))
+    ));
+
+    let coepi_only =
+        ReportPolicy::new(100).allow_memo_type(MemoType::CoEpiV1, MemoPolicy::default());
+    assert!(coepi_only.memo_policy(MemoType::CovidWatchV1).is_none());
+    assert!(matches!(
+        check(
+            &coepi_only,
+            report(MemoType::CovidWatchV1, b"positive", 1, 2)
+        ),
+        Err(Error::MemoTypeNotAllowed(1))
+    ));
+
+    // The reserved memo type can never be allowed.
+    let reserved = coepi_only.allow_memo_type(MemoType::Reserved, MemoPolicy::default());
+    assert!(reserved.memo_policy(MemoType::Reserved).is_none());
+}
+
+#[test]
+fn default_policy() {
+    let policy = ReportPolicy::default();
+    assert_eq!(policy.max_index_span(), 14 * 24 * 4);
+    assert!(check(&policy, report(MemoType::CoEpiV1, &[0; 255], 1, 1344)).is_ok());
+    assert!(check(&policy, report(MemoType::CovidWatchV1, b"", 1000, 2000)).is_ok());
+    assert!(matches!(
+        check(&policy, report(MemoType::CoEpiV1, b"", 1, 1345)),
+        Err(Error::ReportRangeTooLarge(1345))
+    ));
+
+    // The policy is checked before the signature.
+    let mut bytes = Vec::new();
+    report(MemoType::CoEpiV1, b"", 2, 1)
+        .write(&mut bytes)
+        .unwrap();
+    *bytes.last_mut().unwrap() ^= 1;
+    let (signed_report, _) = SignedReportRef::parse(&bytes).unwrap();
+    assert!(matches!(
+        signed_report.verify_with_policy(&policy),
+        Err(Error::ReportRangeInverted(2, 1))
+    ));
+    assert!(matches!(
+        signed_report.verify(),
+        Err(Error::ReportVerificationFailed)
+    ));
+}
+
+#[test]
+fn extended_memo_limits() {
+    let extended = ReportAuthorizationKey::new(rand::thread_rng())
+        .create_versioned_report(ReportVersion::V3, MemoType::CoEpiV1, vec![0; 1000], 1, 2)
+        .unwrap();
+
+    // Extended memos must be allowed explicitly.
+    assert!(matches!(
+        check(&ReportPolicy::default(), extended.clone()),
+        Err(Error::MemoTooLong(1000))
+    ));
+    let policy = ReportPolicy::new(100).allow_memo_type(
+        MemoType::CoEpiV1,
+        MemoPolicy {
+            max_len: 4096,
+            schema: MemoSchema::Any,
+        },
+    );
+    assert!(check(&policy, extended).is_ok());
+}
@@ -0,0 +1,207 @@
+//! Property-based tests for the ratchet and report invariants.
+
+use std::sync::OnceLock;
+
+use proptest::prelude::*;
+use tcn::*;
+
+fn rak_strategy() -> impl Strategy<Value = ReportAuthorizationKey> {
+    any::<[u8; 32]>().prop_map(|bytes| {
+        ReportAuthorizationKey::decode_from_slice(&bytes).expect("any 32 bytes are a valid key")
+    })
+}
+
+fn memo_type_strategy() -> impl Strategy<Value = MemoType> {
+    prop_oneof![Just(MemoType::CoEpiV1), Just(MemoType::CovidWatchV1)]
+}
+
+/// Independently ratchet from `tck_1`, returning `tcn_1..=tcn_{count}`.
+fn ratcheted_chain(rak: &ReportAuthorizationKey, count: usize) -> Vec<TemporaryContactNumber> {
+    let mut tcns = Vec::with_capacity(count);
+    let mut tck = Some(rak.initial_temporary_contact_key());
+    while let Some(key) = tck.take() {
+        if tcns.len() == count {
+            break;
+        }
+        tcns.push(key.temporary_contact_number());
+        tck = key.ratchet();
+    }
+    tcns
+}
+
+/// A fixed key and its entire TCN chain, shared by the tests that need indices
+/// near `u16::MAX`.
+fn full_chain() -> &'static (ReportAuthorizationKey, Vec<TemporaryContactNumber>) {
+    static CHAIN: OnceLock<(ReportAuthorizationKey, Vec<TemporaryContactNumber>)> = OnceLock::new();
+    CHAIN.get_or_init(|| {
+        let rak = ReportAuthorizationKey::decode_from_slice(&[0x38; 32]).unwrap();
+        let tcns = ratcheted_chain(&rak, u16::MAX as usize);
+        (rak, tcns)
+    })
+}
+
+/// The TCNs a report created with `(j_1, j_2)` should disclose, given the
+/// chain `tcn_1..`.
+fn expected_tcns(tcns: &[TemporaryContactNumber], j_1: u16, j_2: u16) -> &[TemporaryContactNumber] {
+    // j_1 = 0 is treated as j_1 = 1, and j_2 < j_1 discloses nothing.
+    let j_1 = j_1.max(1) as usize;
+    let j_2 = j_2 as usize;
+    if j_2 < j_1 {
+        &[]
+    } else {
+        &tcns[j_1 - 1..j_2]
+    }
+}
+
+fn report_tcns(
+    rak: &ReportAuthorizationKey,
+    j_1: u16,
+    j_2: u16,
+) -> (Report, Vec<TemporaryContactNumber>) {
+    let report = rak
+        .create_report(MemoType::CoEpiV1, b"symptom data".to_vec(), j_1, j_2)
+        .unwrap()
+        .verify()
+        .unwrap();
+    let tcns = report.temporary_contact_numbers().collect();
+    (report, tcns)
+}
+
+proptest! {
+    #[test]
+    fn report_tcns_match_ratcheted_chain(
+        rak in rak_strategy(),
+        j_1 in 0u16..512,
+        len in -4i32..64,
+    ) {
+        let j_2 = (j_1 as i32 + len).max(0) as u16;
+        let chain = ratcheted_chain(&rak, j_2.max(1) as usize);
+
+        let (report, tcns) = report_tcns(&rak, j_1, j_2);
+        prop_assert_eq!(&tcns[..], expected_tcns(&chain, j_1, j_2));
+        prop_assert_eq!(report.index_range(), j_1.max(1)..=j_2);
+    }
+
+    #[test]
+    fn serialization_round_trips_exactly(
+        rak in rak_strategy(),
+        memo_type in memo_type_strategy(),
+        memo_data in prop::collection::vec(any::<u8>(), 0..=255),
+        j_1 in 0u16..256,
+        j_2 in any::<u16>(),
+        tck_bytes in prop::collection::vec(any::<u8>(), 66),
+    ) {
+        let signed_report = rak.create_report(memo_type, memo_data, j_1, j_2).unwrap();
+        let mut bytes = Vec::new();
+        signed_report.write(&mut bytes).unwrap();
+        let memo_len = signed_report.clone().verify().unwrap().memo_data().len();
+        prop_assert_eq!(bytes.len(), 134 + memo_len);
+
+        let mut rewritten = Vec::new();
+        SignedReport::read(&bytes[..]).unwrap().write(&mut rewritten).unwrap();
+        prop_assert_eq!(&rewritten, &bytes);
+        let mut encoded = vec![0; bytes.len()];
+        SignedReport::decode_from_slice(&bytes)
+            .unwrap()
+            .encode_to_slice(&mut encoded)
+            .unwrap();
+        prop_assert_eq!(&encoded, &bytes);
+
+        let report_bytes = &bytes[..bytes.len() - 64];
+        let mut rewritten = Vec::new();
+        Report::read(report_bytes).unwrap().write(&mut rewritten).unwrap();
+        prop_assert_eq!(&rewritten[..], report_bytes);
+
+        let mut rewritten = [0; 32];
+        let mut rak_bytes = [0; 32];
+        rak.encode_to_slice(&mut rak_bytes).unwrap();
+        ReportAuthorizationKey::decode_from_slice(&rak_bytes)
+            .unwrap()
+            .encode_to_slice(&mut rewritten)
+            .unwrap();
+        prop_assert_eq!(rewritten, rak_bytes);
+
+        let tck = TemporaryContactKey::decode_from_slice(&tck_bytes).unwrap();
+        let mut rewritten = Vec::new();
+        TemporaryContactKey::read(&tck_bytes[..]).unwrap().write(&mut rewritten).unwrap();
+        prop_assert_eq!(&rewritten, &tck_bytes);
+        prop_assert_eq!(tck.index(), u16::from_le_bytes([tck_bytes[0], tck_bytes[1]]));
+    }
+
+    #[test]
+    fn any_single_bit_flip_fails_verification(
+        rak in rak_strategy(),
+        memo_data in prop::collection::vec(any::<u8>(), 0..32),
+        j_1 in 1u16..64,
+        j_2 in 1u16..128,
+        bit in any::<prop::sample::Index>(),
+    ) {
+        let mut bytes = Vec::new();
+        rak.create_report(MemoType::CoEpiV1, memo_data, j_1, j_2)
+            .unwrap()
+            .write(&mut bytes)
+            .unwrap();
+        let bit = bit.index(bytes.len() * 8);
+        bytes[bit / 8] ^= 1 << (bit % 8);
+
+        // The flip may already make the report unparseable, but it must never
+        // produce a report that verifies.
+        let owned = SignedReport::decode_from_slice(&bytes).and_then(SignedReport::verify);
+        prop_assert!(owned.is_err(), "bit {} flipped and still verified", bit);
+        let borrowed = SignedReportRef::parse(&bytes).and_then(|(r, _)| r.verify());
+        prop_assert!(borrowed.is_err(), "bit {} flipped and still verified", bit);
+    }
+}
+
+proptest! {
+    // Each case ratchets up to 65535 times, so run fewer of them.
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn report_tcns_match_ratcheted_chain_at_any_index(
+        j_1 in any::<u16>(),
+        len in 0u16..64,
+    ) {
+        let (rak, chain) = full_chain();
+        let j_2 = j_1.saturating_add(len);
+
+        let (_, tcns) = report_tcns(rak, j_1, j_2);
+        prop_assert_eq!(&tcns[..], expected_tcns(chain, j_1, j_2));
+    }
+}
+
+#[test]
+fn index_edge_cases() {
+    let (rak, chain) = full_chain();
+    let last = *chain.last().unwrap();
+
+    // j_1 = 0 is treated as j_1 = 1.
+    let (report, tcns) = report_tcns(rak, 0, 2);
+    assert_eq!(report.index_range(), 1..=2);
+    assert_eq!(tcns, &chain[..2]);
+    assert_eq!(report_tcns(rak, 0, 0).1, vec![]);
+
+    // j_2 < j_1 discloses nothing.
+    assert_eq!(report_tcns(rak, 2, 1).1, vec![]);
+    assert_eq!(report_tcns(rak, u16::MAX, 1).1, vec![]);
+
+    // The last TCN can be reported, alone or as the end of a range.
+    assert_eq!(report_tcns(rak, u16::MAX, u16::MAX).1, vec![last]);
+    assert_eq!(report_tcns(rak, 1, u16::MAX).1, *chain);
+
+    // The ratchet stops after tck_{u16::MAX}.
+    let mut tck_bytes = [0; 66];
+    rak.initial_temporary_contact_key()
+        .encode_to_slice(&mut tck_bytes)
+        .unwrap();
+    tck_bytes[..2].copy_from_slice(&(u16::MAX - 1).to_le_bytes());
+    let tck = TemporaryContactKey::decode_from_slice(&tck_bytes).unwrap();
+    let tck = tck.ratchet().unwrap();
+    assert_eq!(tck.index(), u16::MAX);
+    assert!(tck.ratchet().is_none());
+
+    // tck_1 is the initial key, and derives tcn_1.
+    let tck_1 = rak.initial_temporary_contact_key();
+    assert_eq!(tck_1.index(), 1);
+    assert_eq!(tck_1.temporary_contact_number(), chain[0]);
+}
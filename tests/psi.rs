@@ -0,0 +1,120 @@
+#![cfg(feature = "psi")]
+
+use tcn::*;
+
+fn tcns(rak: &ReportAuthorizationKey, count: usize) -> Vec<TemporaryContactNumber> {
+    let mut tck = rak.initial_temporary_contact_key();
+    let mut tcns = Vec::new();
+    for _ in 0..count {
+        tcns.push(tck.temporary_contact_number());
+        tck = tck.ratchet().unwrap();
+    }
+    tcns
+}
+
+fn report(rak: &ReportAuthorizationKey, j_1: u16, j_2: u16) -> Report {
+    rak.create_report(MemoType::CoEpiV1, Vec::new(), j_1, j_2)
+        .unwrap()
+        .verify()
+        .unwrap()
+}
+
+#[test]
+fn psi_matching() {
+    let reported = ReportAuthorizationKey::new(rand::thread_rng());
+    let unreported = ReportAuthorizationKey::new(rand::thread_rng());
+    let reported_tcns = tcns(&reported, 100);
+    let unreported_tcns = tcns(&unreported, 100);
+
+    let mut server = PsiServer::new(rand::thread_rng());
+    server.insert_report(&report(&reported, 20, 90));
+    // Overlapping reports are deduplicated.
+    server.insert_report(&report(&reported, 80, 90));
+    assert_eq!(server.len(), 71);
+    let published = server.encode_set();
+    assert_eq!(published.len(), 71 * 32);
+    // The published set does not contain the reported TCNs.
+    for tcn in &reported_tcns {
+        assert!(!published.windows(16).any(|window| window == tcn.0));
+    }
+
+    // The client sends its observations over the wire, blinded.
+    let observations = [&unreported_tcns[..10], &reported_tcns[15..25]].concat();
+    let (client, query) = PsiClient::new(&observations, rand::thread_rng());
+    let mut query_bytes = vec![0; query.encoded_len()];
+    query.encode_to_slice(&mut query_bytes).unwrap();
+    let query = PsiQuery::decode_from_slice(&query_bytes).unwrap();
+    let response = server.evaluate(&query).unwrap();
+    let mut response_bytes = vec![0; response.encoded_len()];
+    response.encode_to_slice(&mut response_bytes).unwrap();
+    let response = PsiResponse::decode_from_slice(&response_bytes).unwrap();
+
+    // TCN i is at position i - 1.
+    assert_eq!(
+        client.matches(&response, &published).unwrap(),
+        reported_tcns[19..25]
+    );
+    assert!(client.intersects(&response, &published).unwrap());
+
+    let (client, query) = PsiClient::new(&unreported_tcns, rand::thread_rng());
+    let response = server.evaluate(&query).unwrap();
+    assert!(!client.intersects(&response, &published).unwrap());
+}
+
+#[test]
+fn psi_queries_are_blinded() {
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let observations = tcns(&rak, 5);
+
+    // The same observations are blinded differently in every query.
+    let (_, query_1) = PsiClient::new(&observations, rand::thread_rng());
+    let (_, query_2) = PsiClient::new(&observations, rand::thread_rng());
+    let mut bytes_1 = vec![0; query_1.encoded_len()];
+    query_1.encode_to_slice(&mut bytes_1).unwrap();
+    let mut bytes_2 = vec![0; query_2.encoded_len()];
+    query_2.encode_to_slice(&mut bytes_2).unwrap();
+    assert!(bytes_1
+        .chunks(32)
+        .all(|element| !bytes_2.chunks(32).any(|other| other == element)));
+
+    // A response from a server with another key matches nothing.
+    let mut server = PsiServer::new(rand::thread_rng());
+    let other = PsiServer::new(rand::thread_rng());
+    server.insert_report(&report(&rak, 1, 5));
+    let (client, query) = PsiClient::new(&observations, rand::thread_rng());
+    let response = other.evaluate(&query).unwrap();
+    assert!(!client.intersects(&response, &server.encode_set()).unwrap());
+}
+
+#[test]
+fn malformed_psi_messages() {
+    let server = PsiServer::new(rand::thread_rng());
+    let query = PsiQuery::decode_from_slice(&[0xff; 64]).unwrap();
+    assert!(matches!(
+        server.evaluate(&query),
+        Err(Error::InvalidPsiMessage)
+    ));
+    assert!(matches!(
+        PsiQuery::decode_from_slice(&[0; 33]),
+        Err(Error::InvalidLength(33))
+    ));
+
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let (client, query) = PsiClient::new(&tcns(&rak, 2), rand::thread_rng());
+    let response = server.evaluate(&query).unwrap();
+    let published = server.encode_set();
+    assert!(matches!(
+        client.matches(&response, &[0; 31]),
+        Err(Error::InvalidLength(31))
+    ));
+    let truncated = PsiResponse::decode_from_slice(&[]).unwrap();
+    assert!(matches!(
+        client.matches(&truncated, &published),
+        Err(Error::InvalidPsiMessage)
+    ));
+    let mut bytes = [0; 64];
+    assert!(matches!(
+        response.encode_to_slice(&mut bytes[..63]),
+        Err(Error::BufferTooSmall(64))
+    ));
+}
@@ -0,0 +1,143 @@
+use proptest::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use tcn::*;
+
+fn tcns(count: usize) -> Vec<TemporaryContactNumber> {
+    let mut tck = ReportAuthorizationKey::new(rand::thread_rng()).initial_temporary_contact_key();
+    let mut tcns = Vec::new();
+    for _ in 0..count {
+        tcns.push(tck.temporary_contact_number());
+        tck = tck.ratchet().unwrap();
+    }
+    tcns
+}
+
+proptest! {
+    #[test]
+    fn any_threshold_of_shares_reconstructs(
+        tcn in any::<[u8; 16]>().prop_map(TemporaryContactNumber),
+        count in 1u8..=20,
+        threshold in 1u8..=20,
+        seed in any::<u64>(),
+    ) {
+        let threshold = threshold.min(count);
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        let mut shares = tcn.split(threshold, count, &mut rng).unwrap();
+        prop_assert_eq!(shares.len(), usize::from(count));
+
+        // Any `threshold` shares, in any order, reconstruct the TCN.
+        use rand::seq::SliceRandom;
+        shares.shuffle(&mut rng);
+        prop_assert_eq!(TemporaryContactNumber::reconstruct(&shares).unwrap(), tcn);
+        prop_assert_eq!(
+            TemporaryContactNumber::reconstruct(&shares[..usize::from(threshold)]).unwrap(),
+            tcn
+        );
+        if threshold > 1 {
+            prop_assert!(matches!(
+                TemporaryContactNumber::reconstruct(&shares[..usize::from(threshold) - 1]),
+                Err(Error::InvalidShareThreshold(..))
+            ));
+        }
+    }
+}
+
+#[test]
+fn shares_fit_in_advertisements() {
+    let tcn = tcns(1)[0];
+    let shares = tcn.split(3, 5, rand::thread_rng()).unwrap();
+    for (i, share) in shares.iter().enumerate() {
+        let mut bytes = [0; 22];
+        assert_eq!(share.encode_to_slice(&mut bytes).unwrap(), 22);
+        assert_eq!(TcnShare::decode_from_slice(&bytes).unwrap(), *share);
+        assert_eq!(share.tag(), shares[0].tag());
+        assert_eq!(share.threshold(), 3);
+        assert_eq!(share.index() as usize, i + 1);
+        // No single share reveals the TCN.
+        assert!(!bytes.windows(16).any(|window| window == tcn.0));
+    }
+    assert!(matches!(
+        TcnShare::decode_from_slice(&[0; 21]),
+        Err(Error::InvalidLength(21))
+    ));
+
+    let mut buf = [TcnShare::default(); 4];
+    tcn.split_into(2, rand::thread_rng(), &mut buf).unwrap();
+    assert_eq!(TemporaryContactNumber::reconstruct(&buf[2..]).unwrap(), tcn);
+}
+
+#[test]
+fn invalid_share_parameters() {
+    let tcn = tcns(1)[0];
+    for &(threshold, count) in [(0, 3), (4, 3), (1, 0)].iter() {
+        assert!(matches!(
+            tcn.split(threshold, count, rand::thread_rng()),
+            Err(Error::InvalidShareThreshold(t, c)) if t == threshold && c == count as usize
+        ));
+    }
+    let mut too_many = vec![TcnShare::default(); 256];
+    assert!(matches!(
+        tcn.split_into(2, rand::thread_rng(), &mut too_many),
+        Err(Error::InvalidShareThreshold(2, 256))
+    ));
+
+    let shares = tcn.split(2, 3, rand::thread_rng()).unwrap();
+    assert!(matches!(
+        TemporaryContactNumber::reconstruct(&[shares[1], shares[1]]),
+        Err(Error::InvalidShare(2))
+    ));
+    // Shares from two splits of the same TCN have different tags.
+    let other = tcn.split(2, 3, rand::thread_rng()).unwrap();
+    assert!(matches!(
+        TemporaryContactNumber::reconstruct(&[shares[0], other[1]]),
+        Err(Error::InvalidShare(2))
+    ));
+    assert!(matches!(
+        TemporaryContactNumber::reconstruct(&[]),
+        Err(Error::InvalidShareThreshold(0, 0))
+    ));
+}
+
+#[test]
+fn reassembler_groups_interleaved_shares() {
+    let tcns = tcns(3);
+    let shares = tcns
+        .iter()
+        .map(|tcn| tcn.split(3, 6, rand::thread_rng()).unwrap())
+        .collect::<Vec<_>>();
+
+    // Advertisements from three devices arrive interleaved, with repeats.
+    let mut reassembler = ShareReassembler::new(16);
+    let mut reconstructed = Vec::new();
+    for round in [0, 0, 5, 3, 1].iter() {
+        for device in shares.iter() {
+            reconstructed.extend(reassembler.push(device[*round]));
+        }
+    }
+    assert_eq!(reconstructed, tcns);
+    // Later shares of reconstructed TCNs are ignored.
+    assert_eq!(reassembler.push(shares[0][2]), None);
+    assert_eq!(reassembler.len(), 3);
+    reassembler.clear();
+    assert!(reassembler.is_empty());
+}
+
+#[test]
+fn reassembler_is_bounded() {
+    let tcns = tcns(5);
+    let shares = tcns
+        .iter()
+        .map(|tcn| tcn.split(2, 2, rand::thread_rng()).unwrap())
+        .collect::<Vec<_>>();
+
+    // The first share of every TCN is forgotten by the time its second share
+    // arrives, except for the most recent ones.
+    let mut reassembler = ShareReassembler::new(2);
+    for device in shares.iter() {
+        assert_eq!(reassembler.push(device[0]), None);
+    }
+    assert_eq!(reassembler.len(), 2);
+    assert_eq!(reassembler.push(shares[0][1]), None);
+    assert_eq!(reassembler.push(shares[4][1]), Some(tcns[4]));
+}
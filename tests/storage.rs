@@ -0,0 +1,127 @@
+#![cfg(feature = "storage")]
+
+use tcn::*;
+
+// Cheap parameters so the tests run quickly; real apps should use the default.
+const TEST_PARAMS: PasswordParams = PasswordParams {
+    m_cost: 64,
+    t_cost: 1,
+    p_cost: 1,
+};
+
+fn key_ring() -> KeyRing {
+    let entries = (0..3)
+        .map(|i| {
+            let rak = ReportAuthorizationKey::new(rand::thread_rng());
+            let mut tck = rak.initial_temporary_contact_key();
+            for _ in 0..i * 10 {
+                tck = tck.ratchet().unwrap();
+            }
+            KeyRingEntry { rak, tck }
+        })
+        .collect();
+    KeyRing { entries }
+}
+
+fn assert_same(a: &KeyRing, b: &KeyRing) {
+    assert_eq!(a.entries.len(), b.entries.len());
+    for (a, b) in a.entries.iter().zip(b.entries.iter()) {
+        assert_eq!(a.rak.expose_secret_hex(), b.rak.expose_secret_hex());
+        assert_eq!(a.tck, b.tck);
+    }
+}
+
+#[test]
+fn key_ring_round_trips() {
+    let key_ring = key_ring();
+
+    let key = [7; 32];
+    let container = key_ring.seal_with_key(&key, rand::thread_rng()).unwrap();
+    assert_same(
+        &KeyRing::open_with_key(&container, &key).unwrap(),
+        &key_ring,
+    );
+
+    let container = key_ring
+        .seal_with_password(b"correct horse", TEST_PARAMS, rand::thread_rng())
+        .unwrap();
+    assert_same(
+        &KeyRing::open_with_password(&container, b"correct horse").unwrap(),
+        &key_ring,
+    );
+
+    // The secret keys do not appear in the container.
+    for entry in key_ring.entries.iter() {
+        let mut rak = [0; 32];
+        entry.rak.encode_to_slice(&mut rak).unwrap();
+        assert!(!container.windows(32).any(|w| w == rak));
+    }
+}
+
+#[test]
+fn key_ring_rejects_wrong_keys_and_tampering() {
+    let key_ring = key_ring();
+
+    let container = key_ring
+        .seal_with_password(b"correct horse", TEST_PARAMS, rand::thread_rng())
+        .unwrap();
+    assert!(matches!(
+        KeyRing::open_with_password(&container, b"battery staple"),
+        Err(Error::StorageDecryptionFailed)
+    ));
+    assert!(matches!(
+        KeyRing::open_with_key(&container, &[0; 32]),
+        Err(Error::InvalidStorageContainer)
+    ));
+
+    // Any modification of the header or ciphertext is detected.  Skip the
+    // Argon2 parameters, whose high bits would make the test slow.
+    for i in (0..6).chain(18..container.len()) {
+        let mut tampered = container.clone();
+        tampered[i] ^= 0x01;
+        assert!(KeyRing::open_with_password(&tampered, b"correct horse").is_err());
+    }
+    let mut tampered = container.clone();
+    tampered[6] ^= 0x01;
+    assert!(matches!(
+        KeyRing::open_with_password(&tampered, b"correct horse"),
+        Err(Error::StorageDecryptionFailed)
+    ));
+
+    let mut future = container.clone();
+    future[4] = 2;
+    assert!(matches!(
+        KeyRing::open_with_password(&future, b"correct horse"),
+        Err(Error::UnsupportedStorageVersion(2))
+    ));
+    assert!(matches!(
+        KeyRing::open_with_password(&container[..20], b"correct horse"),
+        Err(Error::InvalidStorageContainer)
+    ));
+}
+
+#[test]
+fn key_ring_rejects_excessive_password_params() {
+    let container = key_ring()
+        .seal_with_password(b"correct horse", TEST_PARAMS, rand::thread_rng())
+        .unwrap();
+
+    // The memory, time and parallelism costs follow the version and KDF bytes.
+    for &(offset, cost) in [(6, (1u32 << 18) + 1), (10, 17), (14, 17)].iter() {
+        let mut expensive = container.clone();
+        expensive[offset..offset + 4].copy_from_slice(&cost.to_le_bytes());
+        assert!(matches!(
+            KeyRing::open_with_password(&expensive, b"correct horse"),
+            Err(Error::InvalidStorageContainer)
+        ));
+    }
+
+    let params = PasswordParams {
+        m_cost: 1 << 20,
+        ..TEST_PARAMS
+    };
+    assert!(matches!(
+        key_ring().seal_with_password(b"correct horse", params, rand::thread_rng()),
+        Err(Error::InvalidStorageContainer)
+    ));
+}
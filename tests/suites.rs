@@ -0,0 +1,107 @@
+use tcn::*;
+
+fn tcns<S: CipherSuite>(rak: &ReportAuthorizationKey, count: usize) -> Vec<TemporaryContactNumber> {
+    let mut tck = rak.initial_temporary_contact_key_for::<S>();
+    let mut tcns = Vec::new();
+    for _ in 0..count {
+        tcns.push(tck.temporary_contact_number());
+        tck = tck.ratchet().unwrap();
+    }
+    tcns
+}
+
+#[test]
+fn suite_reports_expand_to_suite_tcns() {
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let sha256_tcns = tcns::<Sha256Ed25519>(&rak, 100);
+    let blake3_tcns = tcns::<Blake3Ed25519>(&rak, 100);
+    // The default suite is unchanged.
+    assert_eq!(
+        rak.initial_temporary_contact_key()
+            .temporary_contact_number(),
+        sha256_tcns[0]
+    );
+    assert!(sha256_tcns
+        .iter()
+        .zip(blake3_tcns.iter())
+        .all(|(a, b)| a != b));
+
+    let signed_report = rak
+        .create_suite_report::<Blake3Ed25519>(MemoType::CoEpiV1, b"symptom data".to_vec(), 20, 90)
+        .unwrap();
+    let mut bytes = Vec::new();
+    signed_report.write(&mut bytes).unwrap();
+    let report = signed_report.verify().unwrap();
+    assert_eq!(report.version(), ReportVersion::V2);
+    assert_eq!(report.suite(), CipherSuiteId::Blake3Ed25519);
+    assert_eq!(
+        report.temporary_contact_numbers().collect::<Vec<_>>(),
+        blake3_tcns[19..90]
+    );
+
+    let (parsed, _) = SignedReportRef::parse(&bytes).unwrap();
+    let parsed = parsed.verify().unwrap();
+    assert_eq!(parsed.suite(), CipherSuiteId::Blake3Ed25519);
+    assert!(parsed
+        .temporary_contact_numbers()
+        .eq(blake3_tcns[19..90].iter().copied()));
+
+    let mut buf = [0; 137 + 12];
+    let into = rak
+        .create_suite_report_into::<Blake3Ed25519>(
+            MemoType::CoEpiV1,
+            b"symptom data",
+            20,
+            90,
+            &mut buf[..],
+        )
+        .unwrap();
+    assert_eq!(into.verify().unwrap().suite(), CipherSuiteId::Blake3Ed25519);
+    assert_eq!(bytes, buf[..]);
+}
+
+#[test]
+fn unversioned_reports_use_the_default_suite() {
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    for &version in [ReportVersion::V0, ReportVersion::V1, ReportVersion::V2].iter() {
+        let report = rak
+            .create_versioned_report(version, MemoType::CoEpiV1, Vec::new(), 1, 5)
+            .unwrap()
+            .verify()
+            .unwrap();
+        assert_eq!(report.suite(), CipherSuiteId::Sha256Ed25519);
+        assert_eq!(
+            report.temporary_contact_numbers().collect::<Vec<_>>(),
+            tcns::<Sha256Ed25519>(&rak, 5)
+        );
+    }
+}
+
+#[test]
+fn suite_is_signed() {
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let mut bytes = Vec::new();
+    rak.create_suite_report::<Blake3Ed25519>(MemoType::CoEpiV1, Vec::new(), 1, 5)
+        .unwrap()
+        .write(&mut bytes)
+        .unwrap();
+    assert_eq!(bytes[68..71], [0xff, 2, CipherSuiteId::Blake3Ed25519 as u8]);
+
+    // Relabelling the report with another suite, which would expand it to
+    // different TCNs, invalidates its signature.
+    bytes[70] = CipherSuiteId::Sha256Ed25519 as u8;
+    assert!(matches!(
+        SignedReport::decode_from_slice(&bytes).unwrap().verify(),
+        Err(Error::ReportVerificationFailed)
+    ));
+
+    bytes[70] = 2;
+    assert!(matches!(
+        SignedReport::decode_from_slice(&bytes),
+        Err(Error::UnsupportedCipherSuite(2))
+    ));
+    assert!(matches!(
+        SignedReport::read(&bytes[..]),
+        Err(Error::UnsupportedCipherSuite(2))
+    ));
+}
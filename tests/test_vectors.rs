@@ -34,7 +34,7 @@ fn generate_test_vectors() {
         println!("tck_{}:\n{}", i, hex::encode(tck_bytes));
 
         let tcn = tck.temporary_contact_number();
-        println!("tcn_{}:\n{}", i, hex::encode(&tcn.0));
+        println!("tcn_{}:\n{}", i, hex::encode(tcn.0));
 
         tck = tck.ratchet().unwrap();
     }
@@ -54,3 +54,42 @@ fn generate_test_vectors() {
         .expect("writing should succeed");
     println!("signed_report:\n{}", hex::encode(&buf));
 }
+
+#[test]
+fn master_seed_derivation_vectors() {
+    let seed = MasterSeed::decode_from_slice(&(0..32).collect::<Vec<u8>>()).unwrap();
+    let vectors = [
+        (
+            0,
+            "e3990ade528cdae5ce1ee4f88c32464c9edaf2da8c12f2e44b6d4749f0be6065",
+            "ea4b619dfe0f4a012a0b989400e10e30749eb900ac1369659c2bc7b976199f42",
+        ),
+        (
+            1,
+            "48f6c69b148e530ad3e15cb065650b5134a5d9a73441b3fa533179c0cfe83b12",
+            "6049ce38f41b04d1a8eb3837313e2e54be196b2dbf6b044d106d5129bf07878a",
+        ),
+        (
+            u32::MAX,
+            "6a8b3bd2738a65b5010c132edf6d80ce7444113e858ed483924ca11398261990",
+            "ba11f86a8ff9b93e04fe8d5e7e101e29e13b02f5c20ac8fe57da4daa4981b82b",
+        ),
+    ];
+
+    for (period, rak_hex, rvk_hex) in vectors.iter() {
+        let rak = seed.report_authorization_key(*period);
+        assert_eq!(&rak.expose_secret_hex(), rak_hex);
+
+        let mut buf = Vec::new();
+        rak.initial_temporary_contact_key()
+            .write(&mut buf)
+            .expect("writing should succeed");
+        assert_eq!(&hex::encode(&buf[2..2 + 32]), rvk_hex);
+    }
+
+    // The seed round-trips through its secret encoding.
+    let mut bytes = [0; 32];
+    assert_eq!(seed.encode_to_slice(&mut bytes).unwrap(), 32);
+    assert_eq!(bytes.to_vec(), (0..32).collect::<Vec<u8>>());
+    assert!(format!("{:?}", seed).contains("<redacted>"));
+}
@@ -0,0 +1,271 @@
+use std::convert::TryFrom;
+use tcn::*;
+
+fn signed_bytes(version: ReportVersion, memo_data: &[u8]) -> (SignedReport, Vec<u8>) {
+    let signed_report = ReportAuthorizationKey::new(rand::thread_rng())
+        .create_versioned_report(version, MemoType::CoEpiV1, memo_data.to_vec(), 3, 9)
+        .expect("Report creation can only fail if the memo data is too long");
+    let mut bytes = Vec::new();
+    signed_report.write(&mut bytes).unwrap();
+    (signed_report, bytes)
+}
+
+#[test]
+fn versioned_report_round_trip() {
+    let versions = [
+        ReportVersion::V0,
+        ReportVersion::V1,
+        ReportVersion::V2,
+        ReportVersion::V3,
+    ];
+    for &version in versions.iter() {
+        let (signed_report, bytes) = signed_bytes(version, b"symptom data");
+        let header_len = match version {
+            ReportVersion::V0 => 70,
+            ReportVersion::V1 => 72,
+            ReportVersion::V2 => 73,
+            ReportVersion::V3 => 74,
+        };
+        assert_eq!(bytes.len(), header_len + 12 + 64);
+
+        let read = SignedReport::read(&bytes[..]).unwrap().verify().unwrap();
+        let decoded = SignedReport::decode_from_slice(&bytes)
+            .unwrap()
+            .verify()
+            .unwrap();
+        let (parsed, rest) = SignedReportRef::parse(&bytes).unwrap();
+        assert!(rest.is_empty());
+        let parsed = parsed.verify().unwrap();
+        for report in [read, decoded, parsed.to_report()].iter() {
+            assert_eq!(report.version(), version);
+            assert_eq!(report.memo_type(), MemoType::CoEpiV1);
+            assert_eq!(report.memo_data(), b"symptom data");
+            assert_eq!(report.index_range(), 3..=9);
+        }
+        assert_eq!(parsed.version(), version);
+
+        let report = signed_report.verify().unwrap();
+        let mut unsigned = Vec::new();
+        report.write(&mut unsigned).unwrap();
+        assert_eq!(unsigned, bytes[..bytes.len() - 64]);
+        let parsed = ReportRef::parse_all(&[&unsigned[..], &unsigned[..]].concat())
+            .map(|report| report.unwrap().version())
+            .collect::<Vec<_>>();
+        assert_eq!(parsed, vec![version, version]);
+    }
+}
+
+#[test]
+fn version_is_signed() {
+    // Rewriting a version 1 report in the version 0 layout invalidates its
+    // signature, so a report cannot be downgraded in transit.
+    let (_, v1) = signed_bytes(ReportVersion::V1, b"symptom data");
+    assert_eq!(v1[68..70], [0xff, 1]);
+    let downgraded = [&v1[..68], &v1[70..]].concat();
+    assert!(matches!(
+        SignedReport::decode_from_slice(&downgraded)
+            .unwrap()
+            .verify(),
+        Err(Error::ReportVerificationFailed)
+    ));
+}
+
+#[test]
+fn unsupported_versions_are_rejected() {
+    let (_, mut bytes) = signed_bytes(ReportVersion::V1, b"");
+    for &version in [0, 4, 0xff].iter() {
+        bytes[69] = version;
+        assert!(matches!(
+            SignedReport::decode_from_slice(&bytes),
+            Err(Error::UnsupportedReportVersion(v)) if v == version
+        ));
+        assert!(matches!(
+            SignedReportRef::parse(&bytes),
+            Err(Error::UnsupportedReportVersion(v)) if v == version
+        ));
+        assert!(matches!(
+            SignedReport::read(&bytes[..]),
+            Err(Error::UnsupportedReportVersion(v)) if v == version
+        ));
+    }
+
+    // A legacy parser reads the envelope's escape as the reserved memo type,
+    // which it rejects.
+    assert!(MemoType::try_from(bytes[68]).is_err());
+}
+
+#[test]
+fn reserved_memo_type_is_rejected_on_creation() {
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let reserved = MemoType::Reserved;
+    let is_reserved = |e: Error| matches!(e, Error::UnknownMemoType(0xff));
+
+    assert!(is_reserved(
+        rak.create_report(reserved, Vec::new(), 1, 2).unwrap_err()
+    ));
+    assert!(is_reserved(
+        rak.create_suite_report::<Blake3Ed25519>(reserved, Vec::new(), 1, 2)
+            .unwrap_err()
+    ));
+    let mut buf = [0; 256];
+    assert!(is_reserved(
+        rak.create_report_into(reserved, b"", 1, 2, &mut buf)
+            .unwrap_err()
+    ));
+    assert!(is_reserved(
+        rak.create_suite_report_into::<Blake3Ed25519>(reserved, b"", 1, 2, &mut buf)
+            .unwrap_err()
+    ));
+    for &version in [
+        ReportVersion::V0,
+        ReportVersion::V1,
+        ReportVersion::V2,
+        ReportVersion::V3,
+    ]
+    .iter()
+    {
+        assert!(is_reserved(
+            rak.create_versioned_report(version, reserved, Vec::new(), 1, 2)
+                .unwrap_err()
+        ));
+        assert!(is_reserved(
+            rak.create_versioned_report_into(version, reserved, b"", 1, 2, &mut buf)
+                .unwrap_err()
+        ));
+    }
+}
+
+#[test]
+fn versioned_report_into_buffer() {
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    let mut buf = [0; 72 + 12 + 64];
+    let signed_report = rak
+        .create_versioned_report_into(
+            ReportVersion::V1,
+            MemoType::CoEpiV1,
+            b"symptom data",
+            3,
+            9,
+            &mut buf[..],
+        )
+        .unwrap();
+    let report = signed_report.verify().unwrap();
+    assert_eq!(report.version(), ReportVersion::V1);
+
+    let owned = rak
+        .create_versioned_report(
+            ReportVersion::V1,
+            MemoType::CoEpiV1,
+            b"symptom data".to_vec(),
+            3,
+            9,
+        )
+        .unwrap();
+    let mut bytes = Vec::new();
+    owned.write(&mut bytes).unwrap();
+    assert_eq!(bytes, buf[..]);
+
+    // A buffer sized for a version 0 report is too short.
+    let mut short = [0; 70 + 12 + 64];
+    assert!(rak
+        .create_versioned_report_into(
+            ReportVersion::V1,
+            MemoType::CoEpiV1,
+            b"symptom data",
+            3,
+            9,
+            &mut short[..],
+        )
+        .is_err());
+}
+
+#[test]
+fn extended_memos() {
+    let rak = ReportAuthorizationKey::new(rand::thread_rng());
+    for &len in [256, 1000, u16::MAX as usize].iter() {
+        let (signed_report, bytes) = signed_bytes(ReportVersion::V3, &vec![0xab; len]);
+        assert_eq!(bytes.len(), 74 + len + 64);
+        assert_eq!(bytes[68..74], [0xff, 3, 0, 0, len as u8, (len >> 8) as u8]);
+
+        let decoded = SignedReport::decode_from_slice(&bytes).unwrap();
+        let read = SignedReport::read(&bytes[..]).unwrap();
+        let (parsed, _) = SignedReportRef::parse(&bytes).unwrap();
+        assert_eq!(parsed.verify().unwrap().memo_data().len(), len);
+        for report in [signed_report, decoded, read].iter() {
+            let report = report.clone().verify().unwrap();
+            assert_eq!(report.version(), ReportVersion::V3);
+            assert_eq!(report.memo_data(), &vec![0xab; len][..]);
+        }
+    }
+
+    // Only version 3 carries memos longer than 255 bytes.
+    for &version in [ReportVersion::V0, ReportVersion::V1, ReportVersion::V2].iter() {
+        assert!(matches!(
+            rak.create_versioned_report(version, MemoType::CoEpiV1, vec![0; 256], 1, 1),
+            Err(Error::OversizeMemo(256))
+        ));
+    }
+    assert!(matches!(
+        rak.create_versioned_report(
+            ReportVersion::V3,
+            MemoType::CoEpiV1,
+            vec![0; u16::MAX as usize + 1],
+            1,
+            1
+        ),
+        Err(Error::OversizeMemo(65536))
+    ));
+
+    // Suite reports use version 3 only when they need to.
+    let short = rak
+        .create_suite_report::<Blake3Ed25519>(MemoType::CoEpiV1, vec![0; 255], 1, 1)
+        .unwrap();
+    assert_eq!(short.verify().unwrap().version(), ReportVersion::V2);
+    let long = rak
+        .create_suite_report::<Blake3Ed25519>(MemoType::CoEpiV1, vec![0; 256], 1, 1)
+        .unwrap()
+        .verify()
+        .unwrap();
+    assert_eq!(long.version(), ReportVersion::V3);
+    assert_eq!(long.suite(), CipherSuiteId::Blake3Ed25519);
+
+    // A suite report can ask for version 3 with a short memo, but not for a
+    // version that cannot identify the suite.
+    let short = rak
+        .create_versioned_suite_report::<Blake3Ed25519>(
+            ReportVersion::V3,
+            MemoType::CoEpiV1,
+            vec![0; 12],
+            1,
+            1,
+        )
+        .unwrap()
+        .verify()
+        .unwrap();
+    assert_eq!(short.version(), ReportVersion::V3);
+    assert_eq!(short.suite(), CipherSuiteId::Blake3Ed25519);
+    for &version in [ReportVersion::V0, ReportVersion::V1].iter() {
+        assert!(matches!(
+            rak.create_versioned_suite_report::<Blake3Ed25519>(
+                version,
+                MemoType::CoEpiV1,
+                Vec::new(),
+                1,
+                1
+            ),
+            Err(Error::UnsupportedCipherSuite(1))
+        ));
+    }
+}
+
+#[test]
+fn truncated_extended_memos_are_rejected() {
+    let (_, bytes) = signed_bytes(ReportVersion::V3, &[0xab; 300]);
+    for &len in [72, 73, 74, 74 + 299, bytes.len() - 1].iter() {
+        assert!(matches!(
+            SignedReportRef::parse(&bytes[..len]),
+            Err(Error::InvalidLength(_))
+        ));
+        assert!(SignedReport::read(&bytes[..len]).is_err());
+    }
+}